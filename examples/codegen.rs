@@ -1,5 +1,9 @@
 fn print_usage_and_exit() -> ! {
-    eprintln!("Usage: codegen --template <template.liquid> --write-to <outfile>");
+    eprintln!(
+        "Usage:
+    codegen --template <template.liquid> --write-to <outfile> [--check] [--strict] [--defs <defs.json>] [--overlay <overlay.json>]
+    codegen export --format <json|json-schema|proto|fbs|ts|rbs|rbi|py|pyi|node-types|ast-json-schema> [--write-to <outfile>] [--defs <defs.json>] [--overlay <overlay.json>] [--type-tag <wqp_name|camelcase_name>] [--namespace <RubyModule>]"
+    );
     std::process::exit(1);
 }
 
@@ -16,10 +20,96 @@ fn get_arg(key: &str) -> String {
     })
 }
 
+fn has_flag(key: &str) -> bool {
+    std::env::args().any(|e| e == key)
+}
+
+fn load_defs() -> Result<lib_ruby_parser_nodes::Defs, String> {
+    let defs = match try_arg("--defs") {
+        Some(defs_path) => lib_ruby_parser_nodes::Defs::from_file(defs_path)?,
+        None => lib_ruby_parser_nodes::Defs::builtin(),
+    };
+    match try_arg("--overlay") {
+        Some(overlay_path) => {
+            defs.with_overlay(&lib_ruby_parser_nodes::Overlay::from_file(overlay_path)?)
+        }
+        None => Ok(defs),
+    }
+}
+
+fn defs_or_exit() -> lib_ruby_parser_nodes::Defs {
+    load_defs().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    })
+}
+
+fn type_tag() -> lib_ruby_parser_nodes::generators::TypeTag {
+    let name = try_arg("--type-tag").unwrap_or_else(|| String::from("camelcase_name"));
+    lib_ruby_parser_nodes::generators::TypeTag::from_name(&name).unwrap_or_else(|| {
+        eprintln!("Unsupported type tag {}", name);
+        print_usage_and_exit()
+    })
+}
+
+fn namespace() -> String {
+    try_arg("--namespace").unwrap_or_else(|| String::from("LibRubyParser"))
+}
+
+fn export() {
+    let output = match get_arg("--format").as_str() {
+        "json" => lib_ruby_parser_nodes::metadata::to_json_with(&defs_or_exit()),
+        "json-schema" => lib_ruby_parser_nodes::metadata::JSON_SCHEMA.to_string(),
+        "proto" => {
+            lib_ruby_parser_nodes::generators::protobuf(&defs_or_exit()).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            })
+        }
+        "fbs" => lib_ruby_parser_nodes::generators::flatbuffers(&defs_or_exit()),
+        "ts" => lib_ruby_parser_nodes::generators::typescript(&defs_or_exit(), type_tag()),
+        "rbs" => lib_ruby_parser_nodes::generators::rbs(&defs_or_exit(), &namespace()),
+        "rbi" => lib_ruby_parser_nodes::generators::rbi(&defs_or_exit(), &namespace()),
+        "py" => lib_ruby_parser_nodes::generators::python(&defs_or_exit()),
+        "pyi" => lib_ruby_parser_nodes::generators::python_stub(&defs_or_exit()),
+        "node-types" => lib_ruby_parser_nodes::generators::tree_sitter_node_types(&defs_or_exit()),
+        "ast-json-schema" => {
+            lib_ruby_parser_nodes::generators::ast_json_schema(&defs_or_exit(), type_tag())
+        }
+        other => {
+            eprintln!("Unsupported export format {}", other);
+            print_usage_and_exit()
+        }
+    };
+
+    match try_arg("--write-to") {
+        Some(output_path) => std::fs::write(output_path, output).unwrap(),
+        None => println!("{}", output),
+    }
+}
+
 fn main() {
+    if std::env::args().nth(1).as_deref() == Some("export") {
+        return export();
+    }
+
     let template_path = get_arg("--template");
     let output_path = get_arg("--write-to");
 
-    let rendered = lib_ruby_parser_nodes::LiquidTemplate::new(template_path).render();
-    std::fs::write(output_path, rendered).unwrap();
+    let mut template = lib_ruby_parser_nodes::LiquidTemplate::new(template_path);
+    if has_flag("--strict") {
+        template = template.strict();
+    }
+    if try_arg("--defs").is_some() || try_arg("--overlay").is_some() {
+        template = template.with_defs(&defs_or_exit());
+    }
+
+    if has_flag("--check") {
+        if !template.check(output_path) {
+            std::process::exit(1);
+        }
+    } else {
+        let rendered = template.render();
+        std::fs::write(output_path, rendered).unwrap();
+    }
 }
@@ -0,0 +1,97 @@
+use liquid_core::error::{ResultLiquidExt, ResultLiquidReplaceExt};
+use liquid_core::model::ValueView;
+use liquid_core::Expression;
+use liquid_core::Language;
+use liquid_core::Renderable;
+use liquid_core::Result;
+use liquid_core::Runtime;
+use liquid_core::Template;
+use liquid_core::{BlockReflection, ParseBlock, TagBlock, TagTokenIter};
+use std::io::Write;
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct IndentBlock;
+
+impl BlockReflection for IndentBlock {
+    fn start_tag(&self) -> &str {
+        "indent"
+    }
+
+    fn end_tag(&self) -> &str {
+        "endindent"
+    }
+
+    fn description(&self) -> &str {
+        "Indents every non-empty line of the block by a given number of spaces."
+    }
+}
+
+impl ParseBlock for IndentBlock {
+    fn parse(
+        &self,
+        mut arguments: TagTokenIter<'_>,
+        mut tokens: TagBlock<'_, '_>,
+        options: &Language,
+    ) -> Result<Box<dyn Renderable>> {
+        let width = arguments
+            .expect_next("Number of spaces expected")?
+            .expect_value()
+            .into_result()?;
+        arguments.expect_nothing()?;
+
+        let template = Template::new(
+            tokens
+                .parse_all(options)
+                .trace_with(|| format!("{{% indent {} %}}", width).into())?,
+        );
+
+        tokens.assert_empty();
+        Ok(Box::new(Indent { width, template }))
+    }
+
+    fn reflection(&self) -> &dyn BlockReflection {
+        self
+    }
+}
+
+#[derive(Debug)]
+struct Indent {
+    width: Expression,
+    template: Template,
+}
+
+impl Indent {
+    fn trace(&self) -> String {
+        format!("{{% indent {} %}}", self.width)
+    }
+}
+
+impl Renderable for Indent {
+    fn render_to(&self, writer: &mut dyn Write, runtime: &dyn Runtime) -> Result<()> {
+        let width = self.width.evaluate(runtime)?;
+        let width = width
+            .as_scalar()
+            .and_then(|width| width.to_integer())
+            .filter(|width| *width >= 0)
+            .ok_or_else(|| {
+                liquid_core::Error::with_msg("Non-negative integer expected")
+                    .context("indent", width.to_kstr().into_owned())
+            })
+            .trace_with(|| self.trace().into())?;
+        let padding = " ".repeat(width as usize);
+
+        let mut captured = Vec::new();
+        self.template
+            .render_to(&mut captured, runtime)
+            .trace_with(|| self.trace().into())?;
+        let output = String::from_utf8(captured).expect("render only writes UTF-8");
+
+        for line in output.split_inclusive('\n') {
+            if !line.trim().is_empty() {
+                write!(writer, "{}", padding).replace("Failed to render")?;
+            }
+            write!(writer, "{}", line).replace("Failed to render")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+use liquid_core::error::{ResultLiquidExt, ResultLiquidReplaceExt};
+use liquid_core::model::ValueView;
+use liquid_core::Expression;
+use liquid_core::Language;
+use liquid_core::Renderable;
+use liquid_core::Result;
+use liquid_core::Runtime;
+use liquid_core::Template;
+use liquid_core::{BlockReflection, ParseBlock, TagBlock, TagTokenIter};
+use std::io::Write;
+
+// Every non-blank line of the rendered block is an item,
+// so `{% for %}` loops can emit one item per line without tracking `forloop.last`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct JoinBlock;
+
+impl BlockReflection for JoinBlock {
+    fn start_tag(&self) -> &str {
+        "join"
+    }
+
+    fn end_tag(&self) -> &str {
+        "endjoin"
+    }
+
+    fn description(&self) -> &str {
+        "Joins non-blank lines of the block (trimmed) with a given separator."
+    }
+}
+
+impl ParseBlock for JoinBlock {
+    fn parse(
+        &self,
+        mut arguments: TagTokenIter<'_>,
+        mut tokens: TagBlock<'_, '_>,
+        options: &Language,
+    ) -> Result<Box<dyn Renderable>> {
+        let separator = arguments
+            .expect_next("Separator expected")?
+            .expect_value()
+            .into_result()?;
+        arguments.expect_nothing()?;
+
+        let template = Template::new(
+            tokens
+                .parse_all(options)
+                .trace_with(|| format!("{{% join {} %}}", separator).into())?,
+        );
+
+        tokens.assert_empty();
+        Ok(Box::new(Join {
+            separator,
+            template,
+        }))
+    }
+
+    fn reflection(&self) -> &dyn BlockReflection {
+        self
+    }
+}
+
+#[derive(Debug)]
+struct Join {
+    separator: Expression,
+    template: Template,
+}
+
+impl Join {
+    fn trace(&self) -> String {
+        format!("{{% join {} %}}", self.separator)
+    }
+}
+
+impl Renderable for Join {
+    fn render_to(&self, writer: &mut dyn Write, runtime: &dyn Runtime) -> Result<()> {
+        let separator = self.separator.evaluate(runtime)?.to_kstr().into_owned();
+
+        let mut captured = Vec::new();
+        self.template
+            .render_to(&mut captured, runtime)
+            .trace_with(|| self.trace().into())?;
+        let output = String::from_utf8(captured).expect("render only writes UTF-8");
+
+        let items = output
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        write!(writer, "{}", items.join(&separator)).replace("Failed to render")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+mod indent_block;
+pub use indent_block::IndentBlock;
+
+mod join_block;
+pub use join_block::JoinBlock;
+
+pub(crate) fn all() -> Vec<Box<dyn liquid_core::parser::ParseBlock>> {
+    vec![
+        crate::blocks::IndentBlock.into(),
+        crate::blocks::JoinBlock.into(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LiquidTemplate;
+
+    #[test]
+    fn test_indent() {
+        let template = LiquidTemplate::new_eval(
+            "fn foo() {\n{% indent 4 %}let a = 1;\n\nlet b = 2;\n{% endindent %}}",
+        );
+        assert_eq!(
+            template.render(),
+            "fn foo() {\n    let a = 1;\n\n    let b = 2;\n}"
+        );
+    }
+
+    #[test]
+    fn test_join() {
+        let template = LiquidTemplate::new_eval(
+            "Alias({% join ', ' %}\n{% for field in nodes[0].fields %}\n  {{ field.snakecase_name }}\n{% endfor %}\n{% endjoin %})",
+        );
+        assert_eq!(
+            template.render(),
+            "Alias(to, from, keyword_l, expression_l)"
+        );
+    }
+}
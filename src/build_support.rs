@@ -0,0 +1,130 @@
+use crate::Template;
+use std::path::{Path, PathBuf};
+
+/// Returns `true` if current process is a build script executed by Cargo.
+///
+/// Cargo sets `OUT_DIR`, `TARGET` and `HOST` only when it runs a build script.
+pub fn is_build_script() -> bool {
+    std::env::var_os("OUT_DIR").is_some()
+        && std::env::var_os("TARGET").is_some()
+        && std::env::var_os("HOST").is_some()
+}
+
+pub fn out_dir() -> PathBuf {
+    let out_dir = std::env::var_os("OUT_DIR").unwrap_or_else(|| {
+        eprintln!(
+            "OUT_DIR is not set, build_support::out_dir() must be called from a build script"
+        );
+        std::process::exit(1);
+    });
+    PathBuf::from(out_dir)
+}
+
+pub fn rerun_if_changed<P: AsRef<Path>>(path: P) {
+    println!("cargo:rerun-if-changed={}", path.as_ref().display());
+}
+
+/// Writes `contents` to `path` only if the file doesn't exist or has different content,
+/// so that its mtime (and everything that depends on it) stays untouched otherwise.
+///
+/// Returns `true` if the file has been written.
+pub fn write_if_changed<P: AsRef<Path>>(path: P, contents: &str) -> bool {
+    let path = path.as_ref();
+
+    if let Ok(existing) = std::fs::read_to_string(path) {
+        if existing == contents {
+            return false;
+        }
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).unwrap_or_else(|e| {
+            eprintln!("Failed to create directory {}:\n{}", dir.display(), e);
+            std::process::exit(1);
+        });
+    }
+
+    std::fs::write(path, contents).unwrap_or_else(|e| {
+        eprintln!("Failed to write {}:\n{}", path.display(), e);
+        std::process::exit(1);
+    });
+    true
+}
+
+/// Compares `contents` with the file at `path`.
+///
+/// Returns `true` if the file is up to date. Otherwise prints a unified diff
+/// between the file on disk and `contents` to stderr and returns `false`.
+pub fn check_file<P: AsRef<Path>>(path: P, contents: &str) -> bool {
+    let path = path.as_ref();
+
+    let on_disk = match std::fs::read_to_string(path) {
+        Ok(on_disk) => on_disk,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            eprintln!("{} does not exist", path.display());
+            String::new()
+        }
+        Err(e) => {
+            eprintln!("Failed to read {}:\n{}", path.display(), e);
+            std::process::exit(1);
+        }
+    };
+
+    if on_disk == contents {
+        return true;
+    }
+
+    let diff = similar::TextDiff::from_lines(on_disk.as_str(), contents)
+        .unified_diff()
+        .header(
+            &format!("{} (on disk)", path.display()),
+            &format!("{} (rendered)", path.display()),
+        )
+        .to_string();
+    eprintln!("{} is out of date:\n{}", path.display(), diff);
+    false
+}
+
+/// Renders `template` to `path`, rewriting it only if the content has changed.
+///
+/// When called from a build script also emits `cargo:rerun-if-changed`
+/// for the template and all of its partials.
+pub fn render_to<T: Template, P: AsRef<Path>>(template: T, path: P) -> bool {
+    let rendered = template.render();
+    write_if_changed(path, &rendered)
+}
+
+/// Same as [`render_to`], but `file_name` is resolved relative to `OUT_DIR`.
+///
+/// Returns the full path of the output file.
+pub fn render_to_out_dir<T: Template, P: AsRef<Path>>(template: T, file_name: P) -> PathBuf {
+    let path = out_dir().join(file_name);
+    render_to(template, &path);
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_to, write_if_changed};
+    use crate::LiquidTemplate;
+
+    #[test]
+    fn test_write_if_changed() {
+        let dir = std::env::temp_dir().join(format!(
+            "lib-ruby-parser-nodes-test-build-support-{}",
+            std::process::id()
+        ));
+        let path = dir.join("output.txt");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(write_if_changed(&path, "foo"));
+        assert!(!write_if_changed(&path, "foo"));
+        assert!(write_if_changed(&path, "bar"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "bar");
+
+        assert!(!render_to(LiquidTemplate::new_eval("bar"), &path));
+        assert!(render_to(LiquidTemplate::new_eval("baz"), &path));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
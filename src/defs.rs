@@ -0,0 +1,251 @@
+//! Owned counterparts of [`Node`]/[`Message`] that can be modified and loaded from files.
+//!
+//! [`Defs`] can be passed to templates instead of built-in nodes and messages
+//! (see [`LiquidTemplate::with_defs`](crate::LiquidTemplate::with_defs)),
+//! e.g. to generate code for a modified list of nodes without patching this crate.
+
+use crate::{Message, MessageField, MessageFieldType, Node, NodeField, NodeFieldType};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Custom key/value data attached to nodes, messages and fields,
+/// see [`Overlay`](crate::overlay::Overlay).
+pub type Annotations = BTreeMap<String, serde_json::Value>;
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct NodeDef {
+    pub camelcase_name: String,
+    pub wqp_name: String,
+    #[serde(default)]
+    pub fields: Vec<NodeFieldDef>,
+    #[serde(default)]
+    pub comment: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: Annotations,
+}
+
+impl NodeDef {
+    pub fn upper_name(&self) -> String {
+        crate::helpers::camelcase_to_snakecase(&self.camelcase_name).to_uppercase()
+    }
+
+    pub fn lower_name(&self) -> String {
+        crate::helpers::camelcase_to_snakecase(&self.camelcase_name).to_lowercase()
+    }
+
+    pub fn has_loc_fields(&self) -> bool {
+        self.fields.iter().any(|f| f.field_type.is_loc())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeFieldDef {
+    pub snakecase_name: String,
+    pub field_type: NodeFieldType,
+    #[serde(default)]
+    pub always_print: bool,
+    #[serde(default)]
+    pub comment: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: Annotations,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MessageDef {
+    pub camelcase_name: String,
+    #[serde(default)]
+    pub fields: Vec<MessageFieldDef>,
+    #[serde(default)]
+    pub comment: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: Annotations,
+}
+
+impl MessageDef {
+    pub fn upper_name(&self) -> String {
+        crate::helpers::camelcase_to_snakecase(&self.camelcase_name).to_uppercase()
+    }
+
+    pub fn lower_name(&self) -> String {
+        crate::helpers::camelcase_to_snakecase(&self.camelcase_name).to_lowercase()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageFieldDef {
+    pub snakecase_name: String,
+    pub field_type: MessageFieldType,
+    #[serde(default)]
+    pub comment: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: Annotations,
+}
+
+fn owned_comment(comment: &[&str]) -> Vec<String> {
+    comment.iter().map(|line| line.to_string()).collect()
+}
+
+impl From<&NodeField> for NodeFieldDef {
+    fn from(field: &NodeField) -> Self {
+        Self {
+            snakecase_name: field.snakecase_name.to_string(),
+            field_type: field.field_type.clone(),
+            always_print: field.always_print,
+            comment: owned_comment(field.comment),
+            annotations: Annotations::new(),
+        }
+    }
+}
+
+impl From<&Node> for NodeDef {
+    fn from(node: &Node) -> Self {
+        Self {
+            camelcase_name: node.camelcase_name.to_string(),
+            wqp_name: node.wqp_name.to_string(),
+            fields: node.fields.iter().map(|f| NodeFieldDef::from(*f)).collect(),
+            comment: owned_comment(node.comment),
+            annotations: Annotations::new(),
+        }
+    }
+}
+
+impl From<&MessageField> for MessageFieldDef {
+    fn from(field: &MessageField) -> Self {
+        Self {
+            snakecase_name: field.snakecase_name.to_string(),
+            field_type: field.field_type.clone(),
+            comment: owned_comment(field.comment),
+            annotations: Annotations::new(),
+        }
+    }
+}
+
+impl From<&Message> for MessageDef {
+    fn from(message: &Message) -> Self {
+        Self {
+            camelcase_name: message.camelcase_name.to_string(),
+            fields: message
+                .fields
+                .iter()
+                .map(|f| MessageFieldDef::from(*f))
+                .collect(),
+            comment: owned_comment(message.comment),
+            annotations: Annotations::new(),
+        }
+    }
+}
+
+/// A set of nodes and messages.
+///
+/// Has the same shape as [`metadata::to_json`](crate::metadata::to_json) output
+/// (other top-level keys are ignored), so an exported document can be edited and loaded back.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Defs {
+    #[serde(default)]
+    pub nodes: Vec<NodeDef>,
+    #[serde(default)]
+    pub messages: Vec<MessageDef>,
+}
+
+impl Defs {
+    /// Built-in [`nodes()`](crate::nodes) and [`messages()`](crate::messages).
+    pub fn builtin() -> Self {
+        Self {
+            nodes: crate::nodes().iter().map(|n| NodeDef::from(*n)).collect(),
+            messages: crate::messages()
+                .iter()
+                .map(|m| MessageDef::from(*m))
+                .collect(),
+        }
+    }
+
+    pub fn from_json(src: &str) -> Result<Self, String> {
+        serde_json::from_str(src).map_err(|e| e.to_string())
+    }
+
+    #[cfg(feature = "toml")]
+    pub fn from_toml(src: &str) -> Result<Self, String> {
+        toml::from_str(src).map_err(|e| e.to_string())
+    }
+
+    #[cfg(feature = "serde_yaml")]
+    pub fn from_yaml(src: &str) -> Result<Self, String> {
+        serde_yaml::from_str(src).map_err(|e| e.to_string())
+    }
+
+    /// Loads a file, format is detected by extension: `.json`,
+    /// `.toml` (requires `toml` feature), `.yml`/`.yaml` (requires `serde_yaml` feature).
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, String> {
+        load_file(path.as_ref())
+    }
+
+    /// Merges annotations from an overlay, fails if it refers to an unknown node, message or field.
+    pub fn with_overlay(mut self, overlay: &crate::overlay::Overlay) -> Result<Self, String> {
+        overlay.apply(&mut self)?;
+        Ok(self)
+    }
+}
+
+pub(crate) fn load_file<T: DeserializeOwned>(path: &std::path::Path) -> Result<T, String> {
+    let src = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    let parsed = match extension {
+        "json" => serde_json::from_str(&src).map_err(|e| e.to_string()),
+        #[cfg(feature = "toml")]
+        "toml" => toml::from_str(&src).map_err(|e| e.to_string()),
+        #[cfg(feature = "serde_yaml")]
+        "yml" | "yaml" => serde_yaml::from_str(&src).map_err(|e| e.to_string()),
+        other => Err(format!("unsupported file format {:?}", other)),
+    };
+    parsed.map_err(|e| format!("Failed to load {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_roundtrip() {
+        let defs = Defs::from_json(&crate::metadata::to_json()).unwrap();
+        assert_eq!(defs, Defs::builtin());
+    }
+
+    #[test]
+    fn test_defaults() {
+        let defs = Defs::from_json(
+            r#"{ "nodes": [{ "camelcase_name": "Foo", "wqp_name": "foo", "fields": [{ "snakecase_name": "bar", "field_type": "MaybeLoc" }] }] }"#,
+        )
+        .unwrap();
+        assert_eq!(defs.nodes[0].fields[0].comment, Vec::<String>::new());
+        assert!(defs.nodes[0].has_loc_fields());
+        assert_eq!(defs.messages, vec![]);
+
+        assert!(Defs::from_json(r#"{ "nodes": [{ "camelcase_name": "Foo" }] }"#).is_err());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml() {
+        let defs = Defs::from_toml(
+            r#"
+            [[messages]]
+            camelcase_name = "Custom"
+            fields = [{ snakecase_name = "name", field_type = "Str" }]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(defs.messages[0].upper_name(), "CUSTOM");
+    }
+
+    #[cfg(feature = "serde_yaml")]
+    #[test]
+    fn test_yaml() {
+        let defs = Defs::from_yaml(
+            "nodes:\n  - camelcase_name: FooBar\n    wqp_name: foo_bar\n    comment: [\"Foo\"]\n",
+        )
+        .unwrap();
+        assert_eq!(defs.nodes[0].lower_name(), "foo_bar");
+    }
+}
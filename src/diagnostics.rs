@@ -0,0 +1,672 @@
+// Liquid errors only implement `Display`, so here we parse their text
+// and turn them into rustc-like diagnostics with a source snippet
+// and a description of the loop iteration that triggered the error.
+//
+// The text format is not a public API, `liquid`/`liquid-core` are pinned to an exact version
+// and `test_liquid_error_format` renders real errors to catch changes on upgrade.
+// If the text can't be parsed the raw error is printed with the template path,
+// a source snippet is shown only if the location is unambiguous.
+
+use crate::template::Partial;
+use liquid_core::model::{Value, ValueView};
+use std::collections::HashMap;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Frame {
+    trace: Option<String>,
+    context: Vec<(String, String)>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ParsedError {
+    message: String,
+    frames: Vec<Frame>,
+}
+
+impl ParsedError {
+    fn parse(error: &str) -> Option<Self> {
+        let error = error.strip_prefix("liquid:")?;
+        let mut message = vec![];
+        let mut frames = vec![];
+
+        let mut lines = error.lines().peekable();
+        while let Some(line) =
+            lines.next_if(|line| *line != "  with:" && !line.starts_with("from: "))
+        {
+            message.push(line);
+        }
+
+        for line in lines {
+            if let Some(trace) = line.strip_prefix("from: ") {
+                frames.push(Frame {
+                    trace: Some(trace.to_string()),
+                    context: vec![],
+                });
+            } else if let Some((key, value)) =
+                line.strip_prefix("    ").and_then(|l| l.split_once('='))
+            {
+                if frames.is_empty() {
+                    frames.push(Frame::default());
+                }
+                let frame = frames.last_mut().unwrap();
+                frame.context.push((key.to_string(), value.to_string()));
+            }
+        }
+
+        let message = message.join("\n").trim().to_string();
+        if message.is_empty() {
+            return None;
+        }
+        Some(Self { message, frames })
+    }
+
+    fn own_context(&self, key: &str) -> Option<&str> {
+        self.frames
+            .first()
+            .filter(|frame| frame.trace.is_none())
+            .and_then(|frame| frame.context.iter().find(|(k, _)| k == key))
+            .map(|(_, v)| v.as_str())
+    }
+
+    // Index of the innermost `{% include %}`/`{% render %}` frame and the name of the partial
+    fn partial(&self) -> Option<(usize, &str)> {
+        self.frames.iter().enumerate().find_map(|(idx, frame)| {
+            let trace = frame.trace.as_deref()?;
+            let partial = trace
+                .strip_prefix("{% include ")
+                .or_else(|| trace.strip_prefix("{% render "))?;
+            // context is `<partial expression>=<partial name>`, but it's missing
+            // if the partial failed to compile, then the name is taken from the tag
+            let name = match frame.context.first() {
+                Some((_, name)) => name.as_str(),
+                None => partial.split_whitespace().next()?.trim_matches('"'),
+            };
+            Some((idx, name))
+        })
+    }
+
+    // Line and column of a syntax error, Pest includes them as ` --> 2:6`
+    fn position(&self) -> Option<(usize, usize)> {
+        self.message.split_once("--> ").and_then(|(_, rest)| {
+            let position = rest.split_whitespace().next()?;
+            let (line, column) = position.split_once(':')?;
+            Some((line.parse::<usize>().ok()?, column.parse::<usize>().ok()?))
+        })
+    }
+}
+
+struct Location {
+    line: usize,
+    column: usize,
+    len: usize,
+}
+
+fn location_of(src: &str, offset: usize, len: usize) -> Location {
+    let before = &src[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+    let column = src[line_start..offset].chars().count() + 1;
+    Location { line, column, len }
+}
+
+fn render_snippet(path: &str, src: &str, location: &Location) -> String {
+    let line_no = location.line.to_string();
+    let gutter = " ".repeat(line_no.len());
+    let line = src.lines().nth(location.line - 1).unwrap_or("");
+    format!(
+        "{gutter}--> {path}:{line}:{column}\n{gutter} |\n{line_no} | {src}\n{gutter} | {spaces}{carets}\n{gutter} |\n",
+        gutter = gutter,
+        path = path,
+        line = location.line,
+        column = location.column,
+        line_no = line_no,
+        src = line,
+        spaces = " ".repeat(location.column - 1),
+        carets = "^".repeat(location.len.max(1)),
+    )
+}
+
+/// Formats an error returned by `liquid::Parser::parse`.
+///
+/// Pest already includes a snippet in the message, we only extract line/column from it
+/// to show the template path.
+pub(crate) fn format_parse_error(path: &str, src: &str, error: &liquid_core::Error) -> String {
+    let raw = error.to_string();
+    match ParsedError::parse(&raw) {
+        Some(error) => format_syntax_error(path, src, &error),
+        None => format_raw_error(path, &raw),
+    }
+}
+
+fn format_raw_error(path: &str, raw: &str) -> String {
+    format!("error: {}\n --> {}\n", raw.trim(), path)
+}
+
+// Checks that a position reported by Liquid exists in `src`
+fn is_valid_position(src: &str, line: usize, column: usize) -> bool {
+    match line.checked_sub(1).and_then(|idx| src.lines().nth(idx)) {
+        Some(text) => column >= 1 && column <= text.chars().count() + 1,
+        None => false,
+    }
+}
+
+fn format_syntax_error(path: &str, src: &str, error: &ParsedError) -> String {
+    match error
+        .position()
+        .filter(|(line, column)| is_valid_position(src, *line, *column))
+    {
+        Some((line, column)) => {
+            let hints = error
+                .message
+                .lines()
+                .filter_map(|line| line.trim_start().strip_prefix("= "))
+                .collect::<Vec<_>>();
+            let message = if hints.is_empty() {
+                String::from("syntax error")
+            } else {
+                hints.join("\n")
+            };
+            let location = Location {
+                line,
+                column,
+                len: 1,
+            };
+            format!(
+                "error: {}\n{}",
+                message,
+                render_snippet(path, src, &location)
+            )
+        }
+        None => format!("error: {}\n --> {}\n", error.message, path),
+    }
+}
+
+/// Formats an error returned by `liquid::Template::render`.
+///
+/// Errors inside of partials (including syntax errors, partials are compiled lazily)
+/// are reported against the source of the partial.
+pub(crate) fn format_render_error(
+    path: &str,
+    src: &str,
+    partials: &[Partial],
+    globals: &liquid::Object,
+    error: &liquid_core::Error,
+) -> String {
+    let raw = error.to_string();
+    let error = match ParsedError::parse(&raw) {
+        Some(error) => error,
+        None => return format_raw_error(path, &raw),
+    };
+    let loops = loop_frames(&error.frames);
+
+    // file that has the error and loops inside of it
+    let (path, src, own_loops) = match error.partial() {
+        Some((idx, name)) => match partials.iter().find(|partial| partial.name == name) {
+            Some(partial) => (
+                partial.path.as_str(),
+                partial.src.as_str(),
+                loop_frames(&error.frames[..idx]),
+            ),
+            None => return format_raw_error(path, &raw),
+        },
+        None => (path, src, loop_frames(&error.frames)),
+    };
+
+    if error.position().is_some() {
+        return format_syntax_error(path, src, &error);
+    }
+
+    let mut out = format!("error: {}\n", error.message);
+
+    match find_location(src, &error, &own_loops) {
+        Some(location) => out.push_str(&render_snippet(path, src, &location)),
+        None => out.push_str(&format!(" --> {}\n", path)),
+    }
+
+    if let Some(frame) = error.frames.first().filter(|f| f.trace.is_none()) {
+        for (key, value) in frame.context.iter() {
+            out.push_str(&format!("  = {}={}\n", key, value));
+        }
+    }
+
+    for line in describe_loops(globals, &loops) {
+        out.push_str(&format!("  = {}\n", line));
+    }
+
+    out
+}
+
+// `{% for var in range limit:1, offset:2, reversed %}` with `index=N` context,
+// ordered from the outermost loop to the innermost one.
+struct LoopFrame {
+    trace: String,
+    var_name: String,
+    range: String,
+    limit: Option<usize>,
+    offset: usize,
+    reversed: bool,
+    index: usize,
+}
+
+fn loop_frames(frames: &[Frame]) -> Vec<LoopFrame> {
+    let mut loops = vec![];
+    for frame in frames.iter().rev() {
+        let trace = match &frame.trace {
+            Some(trace) => trace,
+            None => continue,
+        };
+        let inner = match trace
+            .strip_prefix("{% for ")
+            .and_then(|t| t.strip_suffix("%}"))
+        {
+            Some(inner) => inner,
+            None => continue,
+        };
+        let (var_name, rest) = match inner.split_once(" in ") {
+            Some(pair) => pair,
+            None => continue,
+        };
+        let index = frame
+            .context
+            .iter()
+            .find(|(key, _)| key == "index")
+            .and_then(|(_, value)| value.parse::<usize>().ok());
+        let index = match index {
+            Some(index) => index,
+            None => continue,
+        };
+
+        let mut parts = rest.trim().splitn(2, ' ');
+        let range = parts.next().unwrap_or("").to_string();
+        let mut limit = None;
+        let mut offset = 0;
+        let mut reversed = false;
+        for param in parts.next().unwrap_or("").split(',').map(str::trim) {
+            if let Some(value) = param.strip_prefix("limit:") {
+                limit = value.parse().ok();
+            } else if let Some(value) = param.strip_prefix("offset:") {
+                offset = value.parse().unwrap_or(0);
+            } else if param == "reversed" {
+                reversed = true;
+            }
+        }
+
+        loops.push(LoopFrame {
+            trace: format!("{{% for {} in {} %}}", var_name, range),
+            var_name: var_name.to_string(),
+            range,
+            limit,
+            offset,
+            reversed,
+            index,
+        });
+    }
+    loops
+}
+
+// Splits `node["fields"][0]` (or `node.fields.0`) into `["node", "fields", "0"]`
+fn split_path(path: &str) -> Option<Vec<String>> {
+    let mut parts = vec![];
+    let mut current = String::new();
+    let mut chars = path.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => parts.push(std::mem::take(&mut current)),
+            '[' => {
+                if !current.is_empty() {
+                    parts.push(std::mem::take(&mut current));
+                }
+                let mut index = String::new();
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    index.push(c);
+                }
+                parts.push(index.trim_matches(|c| c == '"' || c == '\'').to_string());
+                if chars.peek() == Some(&'.') {
+                    chars.next();
+                }
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    if parts.is_empty() || parts.iter().any(|p| p.is_empty()) {
+        None
+    } else {
+        Some(parts)
+    }
+}
+
+fn resolve(scope: &HashMap<String, Value>, path: &str) -> Option<Value> {
+    let parts = split_path(path)?;
+    let (root, rest) = parts.split_first()?;
+    let mut value = scope.get(root)?.clone();
+    for part in rest {
+        let next = if let Some(array) = value.as_array() {
+            match part.as_str() {
+                "first" => array.first().map(|v| v.to_value()),
+                "last" => array.last().map(|v| v.to_value()),
+                _ => part
+                    .parse::<i64>()
+                    .ok()
+                    .and_then(|idx| array.get(idx))
+                    .map(|v| v.to_value()),
+            }
+        } else if let Some(object) = value.as_object() {
+            object.get(part).map(|v| v.to_value())
+        } else {
+            None
+        };
+        value = next?;
+    }
+    Some(value)
+}
+
+fn describe_value(value: &Value) -> String {
+    if let Some(object) = value.as_object() {
+        for key in ["camelcase_name", "snakecase_name"] {
+            if let Some(name) = object.get(key) {
+                return name.to_kstr().to_string();
+            }
+        }
+    }
+    value.source().to_string()
+}
+
+fn describe_loops(globals: &liquid::Object, loops: &[LoopFrame]) -> Vec<String> {
+    let mut scope = globals
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.clone()))
+        .collect::<HashMap<_, _>>();
+
+    let mut out = vec![];
+    for frame in loops {
+        let item = resolve(&scope, &frame.range).and_then(|range| {
+            let array = range.as_array()?;
+            let mut items = array
+                .values()
+                .skip(frame.offset)
+                .take(frame.limit.unwrap_or(usize::MAX))
+                .collect::<Vec<_>>();
+            if frame.reversed {
+                items.reverse();
+            }
+            let idx = frame.index.checked_sub(1)?;
+            items.get(idx).map(|v| v.to_value())
+        });
+
+        match item {
+            Some(item) => {
+                out.push(format!(
+                    "while rendering {} = {} (iteration {} of {})",
+                    frame.var_name,
+                    describe_value(&item),
+                    frame.index,
+                    frame.trace
+                ));
+                scope.insert(frame.var_name.clone(), item);
+            }
+            None => {
+                out.push(format!(
+                    "while rendering iteration {} of {}",
+                    frame.index, frame.trace
+                ));
+                scope.remove(&frame.var_name);
+            }
+        }
+    }
+    out
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// Finds all occurrences of `needle` in `src` that are not a part of a longer identifier
+fn find_words(src: &str, needle: &str) -> Vec<usize> {
+    let mut found = vec![];
+    let mut start = 0;
+    while let Some(idx) = src[start..].find(needle) {
+        let idx = start + idx;
+        let end = idx + needle.len();
+        let before_ok =
+            needle.starts_with(|c: char| !is_ident_char(c)) || !src[..idx].ends_with(is_ident_char);
+        let after_ok = !src[end..].starts_with(is_ident_char);
+        if before_ok && after_ok {
+            found.push(idx);
+        }
+        start = end;
+    }
+    found
+}
+
+// Liquid doesn't track source positions of render errors, so we search for the failing
+// property/variable (or the innermost loop) and use it only if it occurs exactly once.
+fn find_location(src: &str, error: &ParsedError, loops: &[LoopFrame]) -> Option<Location> {
+    if let Some(index) = error.own_context("requested index") {
+        let index = index.trim_matches('"');
+        // (needle, offset of the index in it)
+        let needles = [
+            (format!(".{}", index), 1),
+            (format!("[\"{}\"]", index), 0),
+            (format!("['{}']", index), 0),
+        ];
+        let found = needles
+            .iter()
+            .flat_map(|(needle, offset)| {
+                find_words(src, needle)
+                    .into_iter()
+                    .map(move |idx| (idx + offset, needle.len() - offset))
+            })
+            .collect::<Vec<_>>();
+        return match found.as_slice() {
+            [(idx, len)] => Some(location_of(src, *idx, *len)),
+            _ => None,
+        };
+    }
+
+    if let Some(variable) = error.own_context("requested variable") {
+        return match find_words(src, variable).as_slice() {
+            [idx] => Some(location_of(src, *idx, variable.len())),
+            _ => None,
+        };
+    }
+
+    let frame = loops.last()?;
+    let needle = format!("for {} in", frame.var_name);
+    match find_words(src, &needle).as_slice() {
+        [idx] => Some(location_of(src, *idx, needle.len())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC: &str = "line1
+{% for node in nodes %}
+  {% for field in node.fields %}
+    {{ field.snakecase_nme }}
+  {% endfor %}
+{% endfor %}
+";
+
+    const RENDER_ERROR: &str = "liquid: Unknown index
+  with:
+    variable=field
+    requested index=snakecase_nme
+    available indexes=field_type, comment, always_print, snakecase_name
+from: {% for field in node[\"fields\"]  %}
+  with:
+    index=2
+from: {% for node in nodes  %}
+  with:
+    index=1
+";
+
+    #[test]
+    fn test_parse_error() {
+        let error = ParsedError::parse(RENDER_ERROR).unwrap();
+        assert_eq!(error.message, "Unknown index");
+        assert_eq!(error.frames.len(), 3);
+        assert_eq!(error.own_context("variable"), Some("field"));
+        assert_eq!(
+            error.frames[1].trace.as_deref(),
+            Some("{% for field in node[\"fields\"]  %}")
+        );
+    }
+
+    fn render_error(src: &str, partials: &[Partial]) -> liquid_core::Error {
+        let mut source = liquid::partials::InMemorySource::new();
+        for partial in partials {
+            source.add(partial.name.as_str(), partial.src.as_str());
+        }
+        let parser = liquid::ParserBuilder::with_stdlib()
+            .partials(liquid::partials::EagerCompiler::new(source))
+            .build()
+            .unwrap();
+        let globals = liquid::object!({ "nodes": crate::nodes() });
+        parser.parse(src).unwrap().render(&globals).unwrap_err()
+    }
+
+    fn partial(name: &str, src: &str) -> Partial {
+        Partial {
+            name: name.to_string(),
+            path: format!("{}.liquid", name),
+            src: src.to_string(),
+        }
+    }
+
+    // Fails if a new version of Liquid changes the text of errors
+    #[test]
+    fn test_liquid_error_format() {
+        let error = ParsedError::parse(&render_error(SRC, &[]).to_string()).unwrap();
+        assert_eq!(error.message, "Unknown index");
+        assert_eq!(error.own_context("variable"), Some("field"));
+        assert_eq!(error.own_context("requested index"), Some("snakecase_nme"));
+        let traces = error
+            .frames
+            .iter()
+            .map(|frame| (frame.trace.as_deref(), frame.context.first().cloned()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            traces[1..],
+            [
+                (
+                    Some("{% for field in node[\"fields\"]  %}"),
+                    Some(("index".to_string(), "1".to_string()))
+                ),
+                (
+                    Some("{% for node in nodes  %}"),
+                    Some(("index".to_string(), "1".to_string()))
+                ),
+            ]
+        );
+
+        let error = render_error("{% include 'part' %}", &[partial("part", "{% if %}")]);
+        let error = ParsedError::parse(&error.to_string()).unwrap();
+        assert_eq!(error.position(), Some((1, 6)));
+        assert_eq!(error.partial(), Some((0, "part")));
+    }
+
+    #[test]
+    fn test_partial_errors() {
+        let globals = liquid::object!({ "nodes": crate::nodes() });
+        let partials = [
+            partial(
+                "fields",
+                "\n{% for f in node.fields %}{{ f.nme }}{% endfor %}",
+            ),
+            partial("invalid", "\n{% if %}"),
+        ];
+
+        let src = "{% for node in nodes %}{% include 'fields' %}{% endfor %}";
+        let error = render_error(src, &partials);
+        let output = format_render_error("main.liquid", src, &partials, &globals, &error);
+        assert!(output.contains(" --> fields.liquid:2:32\n"), "{}", output);
+        assert!(
+            output.contains("= while rendering node = Alias"),
+            "{}",
+            output
+        );
+        assert!(output.contains("= while rendering f = to"), "{}", output);
+
+        let src = "{% include 'invalid' %}";
+        let error = render_error(src, &partials);
+        let output = format_render_error("main.liquid", src, &partials, &globals, &error);
+        assert!(output.contains(" --> invalid.liquid:2:6\n"), "{}", output);
+    }
+
+    #[test]
+    fn test_split_path() {
+        assert_eq!(
+            split_path("node[\"fields\"][0]"),
+            Some(vec!["node".into(), "fields".into(), "0".into()])
+        );
+        assert_eq!(
+            split_path("node.fields.first"),
+            Some(vec!["node".into(), "fields".into(), "first".into()])
+        );
+    }
+
+    #[test]
+    fn test_format_render_error() {
+        let globals = liquid::object!({ "nodes": crate::nodes() });
+        let error = ParsedError::parse(RENDER_ERROR).unwrap();
+        let loops = loop_frames(&error.frames);
+
+        let location = find_location(SRC, &error, &loops).unwrap();
+        assert_eq!((location.line, location.column, location.len), (4, 14, 13));
+
+        assert_eq!(
+            describe_loops(&globals, &loops),
+            vec![
+                "while rendering node = Alias (iteration 1 of {% for node in nodes %})",
+                "while rendering field = from (iteration 2 of {% for field in node[\"fields\"] %})",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unrecognized_errors() {
+        let globals = liquid::object!({ "nodes": crate::nodes() });
+
+        // not a Liquid error text
+        assert!(ParsedError::parse("boom").is_none());
+        assert_eq!(
+            format_raw_error("main.liquid", "boom\n"),
+            "error: boom\n --> main.liquid\n"
+        );
+        // no context to find the location
+        let error = liquid_core::Error::with_msg("boom");
+        assert_eq!(
+            format_render_error("main.liquid", SRC, &[], &globals, &error),
+            "error: boom\n --> main.liquid\n"
+        );
+
+        // ambiguous location, index=0
+        let src = "{% for node in nodes %}{{ node.nme }}{{ node.nme }}{% endfor %}";
+        let error = RENDER_ERROR
+            .replace("snakecase_nme", "nme")
+            .replace("index=2", "index=0");
+        let error = ParsedError::parse(&error).unwrap();
+        let loops = loop_frames(&error.frames);
+        assert!(find_location(src, &error, &loops).is_none());
+        assert_eq!(
+            describe_loops(&globals, &loops)[1],
+            "while rendering iteration 0 of {% for field in node[\"fields\"] %}"
+        );
+
+        // position outside of the source
+        let error = ParsedError::parse("liquid:  --> 20:6\n  |\n  = Value expected.").unwrap();
+        assert_eq!(
+            format_syntax_error("main.liquid", SRC, &error),
+            "error: --> 20:6\n  |\n  = Value expected.\n --> main.liquid\n"
+        );
+    }
+}
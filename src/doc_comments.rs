@@ -0,0 +1,408 @@
+//! Rendering of `comment` arrays (Markdown) to documentation comments of target languages.
+//!
+//! Unlike [`helpers::render_comment`](crate::helpers::render_comment) that only prefixes lines,
+//! embedded Markdown (```` ```text ```` blocks and `` `inline code` ``) is converted
+//! to the markup of a given documentation tool.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocStyle {
+    /// `///` comments, Markdown is kept, node names in backticks become intra-doc links
+    Rustdoc,
+    /// `/** */` comments with `{@code}`, `<pre>` and `<p>`
+    Javadoc,
+    /// `/** */` comments, Markdown is kept (works for both JSDoc and TSDoc)
+    Jsdoc,
+    /// `/** */` comments with `@verbatim`/`@code` blocks
+    Doxygen,
+    /// `#` comments with RDoc markup (`+code+`, indented verbatim blocks)
+    Yard,
+}
+
+impl DocStyle {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "rustdoc" | "rust" => Some(Self::Rustdoc),
+            "javadoc" | "java" => Some(Self::Javadoc),
+            "jsdoc" | "tsdoc" | "js" | "ts" => Some(Self::Jsdoc),
+            "doxygen" | "c" | "cpp" => Some(Self::Doxygen),
+            "yard" | "ruby" => Some(Self::Yard),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Block {
+    Paragraph(Vec<String>),
+    Code { lang: String, lines: Vec<String> },
+}
+
+fn parse_blocks<S: AsRef<str>>(lines: &[S]) -> Vec<Block> {
+    let mut blocks = vec![];
+    let mut paragraph = vec![];
+    let mut code: Option<(String, Vec<String>)> = None;
+
+    for line in lines {
+        let line = line.as_ref();
+        if let Some((lang, mut code_lines)) = code.take() {
+            if line.trim() == "```" {
+                blocks.push(Block::Code {
+                    lang,
+                    lines: code_lines,
+                });
+            } else {
+                code_lines.push(line.to_string());
+                code = Some((lang, code_lines));
+            }
+        } else if let Some(lang) = line.trim().strip_prefix("```") {
+            if !paragraph.is_empty() {
+                blocks.push(Block::Paragraph(std::mem::take(&mut paragraph)));
+            }
+            code = Some((lang.trim().to_string(), vec![]));
+        } else if line.trim().is_empty() {
+            if !paragraph.is_empty() {
+                blocks.push(Block::Paragraph(std::mem::take(&mut paragraph)));
+            }
+        } else {
+            paragraph.push(line.to_string());
+        }
+    }
+
+    // unterminated fence is rendered as a code block anyway
+    if let Some((lang, lines)) = code {
+        blocks.push(Block::Code { lang, lines });
+    }
+    if !paragraph.is_empty() {
+        blocks.push(Block::Paragraph(paragraph));
+    }
+
+    blocks
+}
+
+// Splits a line into (text, is_code) spans on backticks
+fn inline_spans(line: &str) -> Vec<(&str, bool)> {
+    let mut spans = vec![];
+    let mut rest = line;
+    while let Some(start) = rest.find('`') {
+        match rest[start + 1..].find('`') {
+            Some(len) => {
+                spans.push((&rest[..start], false));
+                spans.push((&rest[start + 1..start + 1 + len], true));
+                rest = &rest[start + 2 + len..];
+            }
+            None => break,
+        }
+    }
+    spans.push((rest, false));
+    spans.retain(|(s, is_code)| *is_code || !s.is_empty());
+    spans
+}
+
+fn map_inline(line: &str, text: impl Fn(&str) -> String, code: impl Fn(&str) -> String) -> String {
+    inline_spans(line)
+        .into_iter()
+        .map(|(s, is_code)| if is_code { code(s) } else { text(s) })
+        .collect()
+}
+
+fn is_node_name(s: &str) -> bool {
+    crate::nodes().iter().any(|node| node.camelcase_name == s)
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn body_rustdoc(blocks: &[Block]) -> Vec<String> {
+    render_blocks(
+        blocks,
+        |line| {
+            map_inline(line, str::to_string, |code| {
+                if is_node_name(code) {
+                    format!("[`{}`]", code)
+                } else {
+                    format!("`{}`", code)
+                }
+            })
+        },
+        fenced,
+        false,
+    )
+}
+
+fn body_javadoc(blocks: &[Block]) -> Vec<String> {
+    render_blocks(
+        blocks,
+        |line| {
+            map_inline(
+                line,
+                |text| {
+                    escape_html(text)
+                        .replace('@', "&#64;")
+                        .replace("*/", "*&#47;")
+                },
+                |code| {
+                    // {@code} requires balanced braces and can't contain comment terminator
+                    let balanced = code.matches('{').count() == code.matches('}').count();
+                    if balanced && !code.contains("*/") {
+                        format!("{{@code {}}}", code)
+                    } else {
+                        format!("<code>{}</code>", escape_javadoc_code(code))
+                    }
+                },
+            )
+        },
+        |_lang, lines| {
+            let mut out = vec![String::from("<pre>")];
+            out.extend(lines.iter().map(|line| escape_javadoc_code(line)));
+            out.push(String::from("</pre>"));
+            out
+        },
+        true,
+    )
+}
+
+fn escape_javadoc_code(s: &str) -> String {
+    escape_html(s)
+        .replace('@', "&#64;")
+        .replace('{', "&#123;")
+        .replace('}', "&#125;")
+        .replace("*/", "*&#47;")
+}
+
+fn body_jsdoc(blocks: &[Block]) -> Vec<String> {
+    render_blocks(
+        blocks,
+        |line| {
+            map_inline(
+                line,
+                |text| text.replace('@', "\\@"),
+                |code| format!("`{}`", code),
+            )
+        },
+        fenced,
+        false,
+    )
+}
+
+fn body_doxygen(blocks: &[Block]) -> Vec<String> {
+    render_blocks(
+        blocks,
+        |line| {
+            map_inline(
+                line,
+                |text| text.replace('\\', "\\\\").replace('@', "\\@"),
+                |code| format!("`{}`", code),
+            )
+        },
+        |lang, lines| {
+            let (open, close) = match lang {
+                "" | "text" => (String::from("@verbatim"), "@endverbatim"),
+                lang => (format!("@code{{.{}}}", lang), "@endcode"),
+            };
+            let mut out = vec![open];
+            out.extend(lines.iter().cloned());
+            out.push(close.to_string());
+            out
+        },
+        false,
+    )
+}
+
+fn body_yard(blocks: &[Block]) -> Vec<String> {
+    render_blocks(
+        blocks,
+        |line| {
+            map_inline(line, str::to_string, |code| {
+                if is_node_name(code) {
+                    format!("{{{}}}", code)
+                } else if !code.is_empty() && code.chars().all(|c| c.is_alphanumeric() || c == '_')
+                {
+                    format!("+{}+", code)
+                } else {
+                    format!("<tt>{}</tt>", escape_html(code))
+                }
+            })
+        },
+        |_lang, lines| lines.iter().map(|line| format!("  {}", line)).collect(),
+        false,
+    )
+}
+
+fn fenced(lang: &str, lines: &[String]) -> Vec<String> {
+    let mut out = vec![format!("```{}", lang)];
+    out.extend(lines.iter().cloned());
+    out.push(String::from("```"));
+    out
+}
+
+fn render_blocks(
+    blocks: &[Block],
+    paragraph_line: impl Fn(&str) -> String,
+    code_block: impl Fn(&str, &[String]) -> Vec<String>,
+    html_paragraphs: bool,
+) -> Vec<String> {
+    let mut out = vec![];
+    for (idx, block) in blocks.iter().enumerate() {
+        if idx > 0 {
+            out.push(String::new());
+        }
+        match block {
+            Block::Paragraph(lines) => {
+                for (line_idx, line) in lines.iter().enumerate() {
+                    let line = paragraph_line(line);
+                    if html_paragraphs && idx > 0 && line_idx == 0 {
+                        out.push(format!("<p>{}", line));
+                    } else {
+                        out.push(line);
+                    }
+                }
+            }
+            Block::Code { lang, lines } => out.extend(code_block(lang, lines)),
+        }
+    }
+    out
+}
+
+fn prefixed(prefix: &str, line: &str) -> String {
+    if line.is_empty() {
+        prefix.to_string()
+    } else {
+        format!("{} {}", prefix, line)
+    }
+}
+
+/// Renders comment lines as a documentation comment of a given style.
+///
+/// Like `render_comment`, every line except for the first one is padded with `offset` spaces.
+///
+/// A `*/` inside of a `/** */` comment is written as `*\/` for JSDoc and Doxygen. Neither tool
+/// decodes entities or commands in code blocks, so this visible backslash is the price of
+/// keeping the comment terminated in the right place (Javadoc uses `*&#47;` instead).
+pub fn render_doc_comment<S: AsRef<str>>(lines: &[S], style: DocStyle, offset: usize) -> String {
+    let blocks = parse_blocks(lines);
+
+    let out = match style {
+        DocStyle::Rustdoc => body_rustdoc(&blocks)
+            .iter()
+            .map(|line| prefixed("///", line))
+            .collect::<Vec<_>>(),
+        DocStyle::Yard => body_yard(&blocks)
+            .iter()
+            .map(|line| prefixed("#", line))
+            .collect::<Vec<_>>(),
+        DocStyle::Javadoc | DocStyle::Jsdoc | DocStyle::Doxygen => {
+            let body = match style {
+                DocStyle::Javadoc => body_javadoc(&blocks),
+                DocStyle::Jsdoc => body_jsdoc(&blocks),
+                _ => body_doxygen(&blocks),
+            };
+            let mut out = vec![String::from("/**")];
+            out.extend(
+                body.iter()
+                    // Javadoc body has no terminators left, it uses HTML entities,
+                    // other styles keep a backslash (see above)
+                    .map(|line| prefixed(" *", &line.replace("*/", "*\\/"))),
+            );
+            out.push(String::from(" */"));
+            out
+        }
+    };
+
+    out.join(&format!("\n{}", " ".repeat(offset)))
+}
+
+pub fn render_doc_comment_by_name<S: AsRef<str>>(
+    lines: &[S],
+    style: &str,
+    offset: usize,
+) -> Result<String, String> {
+    let style = DocStyle::from_name(style).ok_or_else(|| format!("unknown doc style {}", style))?;
+    Ok(render_doc_comment(lines, style, offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMENT: &[&str] = &[
+        "Location of the `alias` keyword, see `Alias`",
+        "",
+        "```text",
+        "if /.*/",
+        "~~",
+        "```",
+    ];
+
+    #[test]
+    fn test_rustdoc() {
+        assert_eq!(
+            render_doc_comment(COMMENT, DocStyle::Rustdoc, 4),
+            [
+                "/// Location of the `alias` keyword, see [`Alias`]",
+                "    ///",
+                "    /// ```text",
+                "    /// if /.*/",
+                "    /// ~~",
+                "    /// ```",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_javadoc() {
+        assert_eq!(
+            render_doc_comment(COMMENT, DocStyle::Javadoc, 0),
+            [
+                "/**",
+                " * Location of the {@code alias} keyword, see {@code Alias}",
+                " *",
+                " * <pre>",
+                " * if /.*&#47;",
+                " * ~~",
+                " * </pre>",
+                " */",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_jsdoc_and_doxygen() {
+        let jsdoc = render_doc_comment(COMMENT, DocStyle::Jsdoc, 0);
+        assert!(jsdoc.contains(" * ```text\n * if /.*\\/\n"));
+
+        let doxygen = render_doc_comment(COMMENT, DocStyle::Doxygen, 0);
+        assert!(doxygen.contains(" * @verbatim\n * if /.*\\/\n * ~~\n * @endverbatim"));
+    }
+
+    #[test]
+    fn test_yard() {
+        assert_eq!(
+            render_doc_comment(COMMENT, DocStyle::Yard, 0),
+            [
+                "# Location of the +alias+ keyword, see {Alias}",
+                "#",
+                "#   if /.*/",
+                "#   ~~",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        let lines = ["First `a < b`", "", "Second"];
+        assert_eq!(
+            render_doc_comment(&lines, DocStyle::Javadoc, 0),
+            "/**\n * First {@code a < b}\n *\n * <p>Second\n */"
+        );
+        assert_eq!(
+            render_doc_comment(&lines, DocStyle::Yard, 0),
+            "# First <tt>a &lt; b</tt>\n#\n# Second"
+        );
+    }
+}
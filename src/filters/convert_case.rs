@@ -0,0 +1,85 @@
+use crate::naming::{Acronyms, Case};
+use liquid_core::parser::{FilterArguments, ParameterReflection};
+use liquid_core::Result;
+use liquid_core::Runtime;
+use liquid_core::{Filter, FilterReflection, ParseFilter};
+use liquid_core::{Value, ValueView};
+use std::sync::Arc;
+
+// Unlike other filters this one has a state (acronyms dictionary),
+// so `ParseFilter` and `FilterReflection` are implemented manually.
+#[derive(Clone)]
+pub struct ConvertCase {
+    case: Case,
+    acronyms: Arc<Acronyms>,
+}
+
+impl ConvertCase {
+    pub fn new(case: Case, acronyms: Arc<Acronyms>) -> Self {
+        Self { case, acronyms }
+    }
+
+    pub(crate) fn all(acronyms: &Acronyms) -> Vec<Box<dyn ParseFilter>> {
+        let acronyms = Arc::new(acronyms.clone());
+        Case::all()
+            .into_iter()
+            .map(|case| Self::new(case, Arc::clone(&acronyms)).into())
+            .collect()
+    }
+}
+
+impl FilterReflection for ConvertCase {
+    fn name(&self) -> &str {
+        self.case.filter_name()
+    }
+
+    fn description(&self) -> &str {
+        "Convert identifier to a given case."
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for ConvertCase {
+    fn parse(&self, mut arguments: FilterArguments) -> Result<Box<dyn Filter>> {
+        if arguments.positional.next().is_some() || arguments.keyword.next().is_some() {
+            return Err(liquid_core::Error::with_msg("Invalid number of arguments")
+                .context("cause", "expected no arguments"));
+        }
+
+        Ok(Box::new(ConvertCaseFilter {
+            case: self.case,
+            acronyms: Arc::clone(&self.acronyms),
+        }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
+
+#[derive(Debug)]
+struct ConvertCaseFilter {
+    case: Case,
+    acronyms: Arc<Acronyms>,
+}
+
+impl std::fmt::Display for ConvertCaseFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.case.filter_name())
+    }
+}
+
+impl Filter for ConvertCaseFilter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> Result<Value> {
+        let input = input.to_kstr();
+        let output = crate::naming::convert(&input, self.case, &self.acronyms);
+        Ok(Value::scalar(output))
+    }
+}
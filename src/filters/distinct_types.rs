@@ -0,0 +1,25 @@
+use crate::filters::array_input;
+use liquid_core::Result;
+use liquid_core::Runtime;
+use liquid_core::{Display_filter, Filter, FilterReflection, ParseFilter};
+use liquid_core::{Value, ValueView};
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "distinct_types",
+    description = "Lists unique field types of fields (or of fields of nodes/messages).",
+    parsed(DistinctTypesFilter)
+)]
+pub struct DistinctTypes;
+
+#[derive(Debug, Default, Display_filter)]
+#[name = "distinct_types"]
+struct DistinctTypesFilter;
+
+impl Filter for DistinctTypesFilter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> Result<Value> {
+        let input = array_input(input)?;
+        let output = crate::queries::distinct_types(input);
+        Ok(Value::array(output.into_iter().map(Value::scalar)))
+    }
+}
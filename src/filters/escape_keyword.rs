@@ -0,0 +1,48 @@
+use crate::filters::invalid_input;
+use liquid_core::Expression;
+use liquid_core::Result;
+use liquid_core::Runtime;
+use liquid_core::{
+    Display_filter, Filter, FilterParameters, FilterReflection, FromFilterParameters, ParseFilter,
+};
+use liquid_core::{Value, ValueView};
+
+#[derive(Debug, FilterParameters)]
+struct EscapeKeywordArgs {
+    #[parameter(description = "Target language.", arg_type = "str")]
+    language: Expression,
+
+    #[parameter(
+        description = "Escape style: suffix (default), prefix or raw.",
+        arg_type = "str"
+    )]
+    style: Option<Expression>,
+}
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "escape_keyword",
+    description = "Escape reserved word of a given language.",
+    parameters(EscapeKeywordArgs),
+    parsed(EscapeKeywordFilter)
+)]
+pub struct EscapeKeyword;
+
+#[derive(Debug, FromFilterParameters, Display_filter)]
+#[name = "escape_keyword"]
+struct EscapeKeywordFilter {
+    #[parameters]
+    args: EscapeKeywordArgs,
+}
+
+impl Filter for EscapeKeywordFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> Result<Value> {
+        let args = self.args.evaluate(runtime)?;
+
+        let input = input.to_kstr();
+        let output =
+            crate::keywords::escape_keyword_by_name(&input, &args.language, args.style.as_deref())
+                .map_err(invalid_input)?;
+        Ok(Value::scalar(output))
+    }
+}
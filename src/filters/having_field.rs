@@ -0,0 +1,40 @@
+use crate::filters::array_input;
+use liquid_core::Expression;
+use liquid_core::Result;
+use liquid_core::Runtime;
+use liquid_core::{
+    Display_filter, Filter, FilterParameters, FilterReflection, FromFilterParameters, ParseFilter,
+};
+use liquid_core::{Value, ValueView};
+
+#[derive(Debug, FilterParameters)]
+struct HavingFieldArgs {
+    #[parameter(description = "Field name.", arg_type = "str")]
+    name: Expression,
+}
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "having_field",
+    description = "Selects nodes/messages that have a field with a given name.",
+    parameters(HavingFieldArgs),
+    parsed(HavingFieldFilter)
+)]
+pub struct HavingField;
+
+#[derive(Debug, FromFilterParameters, Display_filter)]
+#[name = "having_field"]
+struct HavingFieldFilter {
+    #[parameters]
+    args: HavingFieldArgs,
+}
+
+impl Filter for HavingFieldFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> Result<Value> {
+        let args = self.args.evaluate(runtime)?;
+
+        let input = array_input(input)?;
+        let output = crate::queries::having_field(input, &args.name);
+        Ok(Value::array(output))
+    }
+}
@@ -0,0 +1,25 @@
+use crate::filters::array_input;
+use liquid_core::Result;
+use liquid_core::Runtime;
+use liquid_core::{Display_filter, Filter, FilterReflection, ParseFilter};
+use liquid_core::{Value, ValueView};
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "loc_fields",
+    description = "Selects fields that hold locations.",
+    parsed(LocFieldsFilter)
+)]
+pub struct LocFields;
+
+#[derive(Debug, Default, Display_filter)]
+#[name = "loc_fields"]
+struct LocFieldsFilter;
+
+impl Filter for LocFieldsFilter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> Result<Value> {
+        let input = array_input(input)?;
+        let output = crate::queries::loc_fields(input);
+        Ok(Value::array(output))
+    }
+}
@@ -0,0 +1,98 @@
+use crate::filters::invalid_input;
+use crate::type_maps::TypeMap;
+use liquid_core::parser::{FilterArguments, ParameterReflection};
+use liquid_core::Expression;
+use liquid_core::Result;
+use liquid_core::Runtime;
+use liquid_core::{Filter, FilterReflection, ParseFilter};
+use liquid_core::{Value, ValueView};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+// Registered type maps are owned by the template,
+// so `ParseFilter` and `FilterReflection` are implemented manually.
+#[derive(Clone)]
+pub struct MapType {
+    type_maps: Arc<BTreeMap<String, TypeMap>>,
+}
+
+impl MapType {
+    pub fn new(type_maps: BTreeMap<String, TypeMap>) -> Self {
+        Self {
+            type_maps: Arc::new(type_maps),
+        }
+    }
+}
+
+static POSITIONAL_PARAMETERS: &[ParameterReflection] = &[ParameterReflection {
+    name: "type_map",
+    description: "Name of the type map (rust, c, cpp, typescript or a custom one).",
+    is_optional: false,
+}];
+
+impl FilterReflection for MapType {
+    fn name(&self) -> &str {
+        "map_type"
+    }
+
+    fn description(&self) -> &str {
+        "Maps field type to a type of target language."
+    }
+
+    fn positional_parameters(&self) -> &'static [ParameterReflection] {
+        POSITIONAL_PARAMETERS
+    }
+
+    fn keyword_parameters(&self) -> &'static [ParameterReflection] {
+        &[]
+    }
+}
+
+impl ParseFilter for MapType {
+    fn parse(&self, mut arguments: FilterArguments) -> Result<Box<dyn Filter>> {
+        let type_map = arguments
+            .positional
+            .next()
+            .ok_or_else(|| invalid_input("type_map argument is required"))?;
+        if arguments.positional.next().is_some() || arguments.keyword.next().is_some() {
+            return Err(invalid_input("map_type expects a single argument"));
+        }
+
+        Ok(Box::new(MapTypeFilter {
+            type_map,
+            type_maps: Arc::clone(&self.type_maps),
+        }))
+    }
+
+    fn reflection(&self) -> &dyn FilterReflection {
+        self
+    }
+}
+
+#[derive(Debug)]
+struct MapTypeFilter {
+    type_map: Expression,
+    type_maps: Arc<BTreeMap<String, TypeMap>>,
+}
+
+impl std::fmt::Display for MapTypeFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "map_type: {}", self.type_map)
+    }
+}
+
+impl Filter for MapTypeFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> Result<Value> {
+        let name = self.type_map.evaluate(runtime)?.to_kstr().into_owned();
+        let type_map = self
+            .type_maps
+            .get(name.as_str())
+            .ok_or_else(|| invalid_input(format!("unknown type map {}", name)))?;
+
+        let field_type = input.to_kstr();
+        let output = type_map
+            .get(&field_type)
+            .ok_or_else(|| invalid_input(format!("type map {} has no {}", name, field_type)))?;
+        Ok(Value::scalar(output.to_string()))
+    }
+}
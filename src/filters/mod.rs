@@ -1,6 +1,9 @@
 mod render_comment;
 pub use render_comment::RenderComment;
 
+mod render_doc_comment;
+pub use render_doc_comment::RenderDocComment;
+
 mod camelcase_to_snakecase;
 pub use camelcase_to_snakecase::CamelcaseToSnakecase;
 
@@ -19,6 +22,33 @@ pub use escape_cpp_keyword::EscapeCppKeyword;
 mod escape_js_keyword;
 pub use escape_js_keyword::EscapeJsKeyword;
 
+mod escape_keyword;
+pub use escape_keyword::EscapeKeyword;
+
+mod string_literal;
+pub use string_literal::StringLiteral;
+
+mod with_field_type;
+pub use with_field_type::WithFieldType;
+
+mod having_field;
+pub use having_field::HavingField;
+
+mod node_fields;
+pub use node_fields::NodeFields;
+
+mod loc_fields;
+pub use loc_fields::LocFields;
+
+mod distinct_types;
+pub use distinct_types::DistinctTypes;
+
+mod convert_case;
+pub use convert_case::ConvertCase;
+
+mod map_type;
+pub use map_type::MapType;
+
 pub fn invalid_input<S>(cause: S) -> liquid_core::Error
 where
     S: Into<liquid_core::model::KString>,
@@ -26,14 +56,43 @@ where
     liquid_core::Error::with_msg("Invalid input").context("cause", cause)
 }
 
+fn array_input(input: &dyn liquid_core::ValueView) -> liquid_core::Result<Vec<liquid_core::Value>> {
+    let input = input
+        .as_array()
+        .ok_or_else(|| invalid_input("Array expected"))?;
+    Ok(input.values().map(|value| value.to_value()).collect())
+}
+
+impl crate::queries::Item for liquid_core::Value {
+    fn str_attr(&self, name: &str) -> Option<String> {
+        let value = liquid_core::ValueView::as_object(self)?.get(name)?;
+        Some(value.to_kstr().to_string())
+    }
+
+    fn list_attr(&self, name: &str) -> Option<Vec<Self>> {
+        let list = liquid_core::ValueView::as_object(self)?
+            .get(name)?
+            .as_array()?;
+        Some(list.values().map(|value| value.to_value()).collect())
+    }
+}
+
 pub(crate) fn all() -> Vec<Box<dyn liquid_core::parser::ParseFilter>> {
     vec![
         crate::filters::RenderComment.into(),
+        crate::filters::RenderDocComment.into(),
         crate::filters::CamelcaseToSnakecase.into(),
         crate::filters::SnakecaseToCamelcase.into(),
         crate::filters::EscapeRustKeyword.into(),
         crate::filters::EscapeCKeyword.into(),
         crate::filters::EscapeCppKeyword.into(),
         crate::filters::EscapeJsKeyword.into(),
+        crate::filters::EscapeKeyword.into(),
+        crate::filters::StringLiteral.into(),
+        crate::filters::WithFieldType.into(),
+        crate::filters::HavingField.into(),
+        crate::filters::NodeFields.into(),
+        crate::filters::LocFields.into(),
+        crate::filters::DistinctTypes.into(),
     ]
 }
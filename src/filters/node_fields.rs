@@ -0,0 +1,25 @@
+use crate::filters::array_input;
+use liquid_core::Result;
+use liquid_core::Runtime;
+use liquid_core::{Display_filter, Filter, FilterReflection, ParseFilter};
+use liquid_core::{Value, ValueView};
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "node_fields",
+    description = "Selects fields that hold child nodes.",
+    parsed(NodeFieldsFilter)
+)]
+pub struct NodeFields;
+
+#[derive(Debug, Default, Display_filter)]
+#[name = "node_fields"]
+struct NodeFieldsFilter;
+
+impl Filter for NodeFieldsFilter {
+    fn evaluate(&self, input: &dyn ValueView, _runtime: &dyn Runtime) -> Result<Value> {
+        let input = array_input(input)?;
+        let output = crate::queries::node_fields(input);
+        Ok(Value::array(output))
+    }
+}
@@ -48,30 +48,8 @@ impl Filter for RenderCommentFilter {
             .as_array()
             .ok_or_else(|| invalid_input("Array of strings expected"))?;
 
-        let lines = input.values().map(|x| x.to_kstr());
-
-        let rendered = lines
-            .enumerate()
-            .map(|(idx, line)| {
-                let mut line = line.to_string();
-                if !line.is_empty() {
-                    line = format!(" {}", line);
-                }
-                let spaces = if idx == 0 {
-                    // Do not add prefix to the first line
-                    String::from("")
-                } else {
-                    " ".repeat(offset as usize)
-                };
-                format!(
-                    "{spaces}{prefix}{line}",
-                    spaces = spaces,
-                    prefix = prefix,
-                    line = line
-                )
-            })
-            .collect::<Vec<_>>()
-            .join("\n");
+        let lines = input.values().map(|x| x.to_kstr()).collect::<Vec<_>>();
+        let rendered = crate::helpers::render_comment(&lines, &prefix, offset as usize);
 
         Ok(Value::scalar(rendered))
     }
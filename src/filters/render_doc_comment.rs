@@ -0,0 +1,56 @@
+use crate::filters::invalid_input;
+use liquid_core::Expression;
+use liquid_core::Result;
+use liquid_core::Runtime;
+use liquid_core::{
+    Display_filter, Filter, FilterParameters, FilterReflection, FromFilterParameters, ParseFilter,
+};
+use liquid_core::{Value, ValueView};
+
+#[derive(Debug, FilterParameters)]
+struct RenderDocCommentArgs {
+    #[parameter(
+        description = "Documentation style: rustdoc, javadoc, jsdoc, doxygen or yard.",
+        arg_type = "str"
+    )]
+    style: Expression,
+
+    #[parameter(description = "Line offset.", arg_type = "integer")]
+    offset: Option<Expression>,
+}
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "render_doc_comment",
+    description = "Renders comment as a documentation comment of a given style.",
+    parameters(RenderDocCommentArgs),
+    parsed(RenderDocCommentFilter)
+)]
+pub struct RenderDocComment;
+
+#[derive(Debug, FromFilterParameters, Display_filter)]
+#[name = "render_doc_comment"]
+struct RenderDocCommentFilter {
+    #[parameters]
+    args: RenderDocCommentArgs,
+}
+
+impl Filter for RenderDocCommentFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> Result<Value> {
+        let args = self.args.evaluate(runtime)?;
+
+        let input = input
+            .as_array()
+            .ok_or_else(|| invalid_input("Array of strings expected"))?;
+
+        let lines = input.values().map(|x| x.to_kstr()).collect::<Vec<_>>();
+        let rendered = crate::doc_comments::render_doc_comment_by_name(
+            &lines,
+            &args.style,
+            args.offset.unwrap_or(0) as usize,
+        )
+        .map_err(invalid_input)?;
+
+        Ok(Value::scalar(rendered))
+    }
+}
@@ -0,0 +1,42 @@
+use crate::filters::invalid_input;
+use liquid_core::Expression;
+use liquid_core::Result;
+use liquid_core::Runtime;
+use liquid_core::{
+    Display_filter, Filter, FilterParameters, FilterReflection, FromFilterParameters, ParseFilter,
+};
+use liquid_core::{Value, ValueView};
+
+#[derive(Debug, FilterParameters)]
+struct StringLiteralArgs {
+    #[parameter(description = "Target language.", arg_type = "str")]
+    language: Expression,
+}
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "string_literal",
+    description = "Renders a string as an escaped string literal of a given language.",
+    parameters(StringLiteralArgs),
+    parsed(StringLiteralFilter)
+)]
+pub struct StringLiteral;
+
+#[derive(Debug, FromFilterParameters, Display_filter)]
+#[name = "string_literal"]
+struct StringLiteralFilter {
+    #[parameters]
+    args: StringLiteralArgs,
+}
+
+impl Filter for StringLiteralFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> Result<Value> {
+        let args = self.args.evaluate(runtime)?;
+
+        let input = input.to_kstr();
+        let output = crate::string_literals::string_literal_by_name(&input, &args.language)
+            .map_err(invalid_input)?;
+
+        Ok(Value::scalar(output))
+    }
+}
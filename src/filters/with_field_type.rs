@@ -0,0 +1,40 @@
+use crate::filters::array_input;
+use liquid_core::Expression;
+use liquid_core::Result;
+use liquid_core::Runtime;
+use liquid_core::{
+    Display_filter, Filter, FilterParameters, FilterReflection, FromFilterParameters, ParseFilter,
+};
+use liquid_core::{Value, ValueView};
+
+#[derive(Debug, FilterParameters)]
+struct WithFieldTypeArgs {
+    #[parameter(description = "Field type (like MaybeNode).", arg_type = "str")]
+    field_type: Expression,
+}
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "with_field_type",
+    description = "Selects nodes/messages that have a field of a given type, or fields of a given type.",
+    parameters(WithFieldTypeArgs),
+    parsed(WithFieldTypeFilter)
+)]
+pub struct WithFieldType;
+
+#[derive(Debug, FromFilterParameters, Display_filter)]
+#[name = "with_field_type"]
+struct WithFieldTypeFilter {
+    #[parameters]
+    args: WithFieldTypeArgs,
+}
+
+impl Filter for WithFieldTypeFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> Result<Value> {
+        let args = self.args.evaluate(runtime)?;
+
+        let input = array_input(input)?;
+        let output = crate::queries::with_field_type(input, &args.field_type);
+        Ok(Value::array(output))
+    }
+}
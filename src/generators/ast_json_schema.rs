@@ -0,0 +1,291 @@
+// JSON Schema of serialized AST (as opposed to `metadata::JSON_SCHEMA` that describes nodes themselves).
+//
+// A node is an object with `type` discriminant and a property per field,
+// optional fields are nullable and not required.
+//
+// Definitions of nodes and messages are namespaced (`node.Alias`, `message.UnterminatedHeredoc`),
+// so custom names never clash with each other or with `Loc`, `Node`, `Diagnostic` and `DiagnosticMessage`.
+
+use super::TypeTag;
+use crate::defs::{Defs, MessageDef, NodeDef};
+use crate::{MessageFieldType, NodeFieldType};
+use serde_json::{json, Map, Value};
+
+fn reference(name: &str) -> Value {
+    json!({ "$ref": format!("#/$defs/{}", name) })
+}
+
+fn node_key(node: &NodeDef) -> String {
+    format!("node.{}", node.camelcase_name)
+}
+
+fn message_key(message: &MessageDef) -> String {
+    format!("message.{}", message.camelcase_name)
+}
+
+fn nullable(schema: Value) -> Value {
+    json!({ "oneOf": [schema, { "type": "null" }] })
+}
+
+fn node_field_schema(field_type: &NodeFieldType) -> Value {
+    let schema = match field_type {
+        NodeFieldType::Node | NodeFieldType::MaybeNode | NodeFieldType::RegexpOptions => {
+            reference("Node")
+        }
+        NodeFieldType::Nodes => json!({ "type": "array", "items": reference("Node") }),
+        NodeFieldType::Loc | NodeFieldType::MaybeLoc => reference("Loc"),
+        NodeFieldType::Str
+        | NodeFieldType::RawStr
+        | NodeFieldType::MaybeStr
+        | NodeFieldType::Chars
+        | NodeFieldType::StringValue => json!({ "type": "string" }),
+        NodeFieldType::U8 => json!({ "type": "integer", "minimum": 0, "maximum": 255 }),
+    };
+    if field_type.is_optional() {
+        nullable(schema)
+    } else {
+        schema
+    }
+}
+
+fn message_field_schema(field_type: &MessageFieldType) -> Value {
+    match field_type {
+        MessageFieldType::Str => json!({ "type": "string" }),
+        MessageFieldType::Byte => json!({ "type": "integer", "minimum": 0, "maximum": 255 }),
+    }
+}
+
+fn with_description(mut schema: Value, comment: &[String]) -> Value {
+    if !comment.is_empty() {
+        schema["description"] = Value::String(comment.join("\n"));
+    }
+    schema
+}
+
+// Object with `type` discriminant, `fields` are (name, schema, required)
+fn object_schema(tag: &str, fields: Vec<(String, Value, bool)>, comment: &[String]) -> Value {
+    let mut properties = Map::new();
+    let mut required = vec![Value::String(String::from("type"))];
+    properties.insert(String::from("type"), json!({ "const": tag }));
+    for (name, schema, is_required) in fields {
+        if is_required {
+            required.push(Value::String(name.clone()));
+        }
+        properties.insert(name, schema);
+    }
+
+    let schema = json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false
+    });
+    with_description(schema, comment)
+}
+
+fn node_schema(node: &NodeDef, tag: TypeTag) -> Value {
+    let fields = node
+        .fields
+        .iter()
+        .map(|field| {
+            (
+                field.snakecase_name.clone(),
+                with_description(node_field_schema(&field.field_type), &field.comment),
+                !field.field_type.is_optional(),
+            )
+        })
+        .collect();
+    let tag = match tag {
+        TypeTag::WqpName => &node.wqp_name,
+        TypeTag::CamelcaseName => &node.camelcase_name,
+    };
+    object_schema(tag, fields, &node.comment)
+}
+
+fn message_schema(message: &MessageDef) -> Value {
+    let fields = message
+        .fields
+        .iter()
+        .map(|field| {
+            (
+                field.snakecase_name.clone(),
+                with_description(message_field_schema(&field.field_type), &field.comment),
+                true,
+            )
+        })
+        .collect();
+    object_schema(&message.camelcase_name, fields, &message.comment)
+}
+
+pub(crate) fn schema(defs: &Defs, tag: TypeTag) -> String {
+    let mut definitions = Map::new();
+
+    definitions.insert(
+        String::from("Loc"),
+        json!({
+            "description": "Location of a node or its part, byte offsets in the source.",
+            "type": "object",
+            "properties": {
+                "begin": { "type": "integer", "minimum": 0 },
+                "end": { "type": "integer", "minimum": 0 }
+            },
+            "required": ["begin", "end"],
+            "additionalProperties": false
+        }),
+    );
+    definitions.insert(
+        String::from("Node"),
+        json!({
+            "description": "Any node, discriminated by `type`.",
+            "oneOf": defs.nodes.iter().map(|node| reference(&node_key(node))).collect::<Vec<_>>()
+        }),
+    );
+    for node in defs.nodes.iter() {
+        definitions.insert(node_key(node), node_schema(node, tag));
+    }
+
+    definitions.insert(
+        String::from("Diagnostic"),
+        json!({
+            "description": "Parser warning or error.",
+            "type": "object",
+            "properties": {
+                "level": { "enum": ["warning", "error"] },
+                "message": reference("DiagnosticMessage"),
+                "loc": reference("Loc")
+            },
+            "required": ["level", "message", "loc"],
+            "additionalProperties": false
+        }),
+    );
+    definitions.insert(
+        String::from("DiagnosticMessage"),
+        json!({
+            "description": "Any diagnostic message, discriminated by `type`.",
+            "oneOf": defs.messages.iter().map(|message| reference(&message_key(message))).collect::<Vec<_>>()
+        }),
+    );
+    for message in defs.messages.iter() {
+        definitions.insert(message_key(message), message_schema(message));
+    }
+
+    let schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "lib-ruby-parser AST",
+        "description": "Serialized AST node, diagnostics can be validated with `#/$defs/Diagnostic`.",
+        "$ref": "#/$defs/Node",
+        "$defs": definitions
+    });
+    serde_json::to_string_pretty(&schema).expect("schema is always serializable")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generators::TypeTag;
+    use crate::Defs;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_schema() {
+        let schema: Value =
+            serde_json::from_str(&super::schema(&Defs::builtin(), TypeTag::WqpName)).unwrap();
+        let defs = &schema["$defs"];
+        assert_eq!(defs.as_object().unwrap().len(), 124 + 90 + 4);
+        assert_eq!(
+            defs["Node"]["oneOf"][0],
+            json!({ "$ref": "#/$defs/node.Alias" })
+        );
+
+        let alias = &defs["node.Alias"];
+        assert_eq!(alias["properties"]["type"], json!({ "const": "alias" }));
+        assert_eq!(
+            alias["required"],
+            json!(["type", "to", "from", "keyword_l", "expression_l"])
+        );
+        assert_eq!(
+            alias["description"],
+            "Represents `alias to from` statement."
+        );
+
+        let if_node = &defs["node.If"];
+        assert_eq!(
+            if_node["properties"]["else_l"]["oneOf"],
+            json!([{ "$ref": "#/$defs/Loc" }, { "type": "null" }])
+        );
+        assert!(!if_node["required"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("else_l")));
+
+        let begin = &defs["node.Begin"];
+        assert_eq!(begin["properties"]["statements"]["type"], "array");
+
+        let heredoc = &defs["message.UnterminatedHeredoc"];
+        assert_eq!(
+            heredoc["properties"]["type"],
+            json!({ "const": "UnterminatedHeredoc" })
+        );
+        assert_eq!(heredoc["properties"]["heredoc_id"]["type"], "string");
+    }
+
+    #[test]
+    fn test_schema_tag() {
+        let schema: Value =
+            serde_json::from_str(&super::schema(&Defs::builtin(), TypeTag::default())).unwrap();
+        assert_eq!(
+            schema["$defs"]["node.AndAsgn"]["properties"]["type"],
+            json!({ "const": "AndAsgn" })
+        );
+
+        // every branch of `oneOf` must have its own `type`
+        for union in ["Node", "DiagnosticMessage"] {
+            let branches = schema["$defs"][union]["oneOf"].as_array().unwrap();
+            let mut tags = branches
+                .iter()
+                .map(|branch| {
+                    let name = branch["$ref"]
+                        .as_str()
+                        .unwrap()
+                        .trim_start_matches("#/$defs/");
+                    schema["$defs"][name]["properties"]["type"]["const"].clone()
+                })
+                .map(|tag| tag.as_str().unwrap().to_string())
+                .collect::<Vec<_>>();
+            tags.sort_unstable();
+            tags.dedup();
+            assert_eq!(tags.len(), branches.len(), "{}", union);
+        }
+    }
+
+    #[test]
+    fn test_schema_names_dont_clash() {
+        let mut defs = Defs::builtin();
+        let mut node = defs.nodes[0].clone();
+        node.camelcase_name = String::from("Diagnostic");
+        defs.nodes.push(node);
+        let mut message = defs.messages[0].clone();
+        message.camelcase_name = String::from("Alias");
+        defs.messages.push(message);
+
+        let schema: Value =
+            serde_json::from_str(&super::schema(&defs, TypeTag::default())).unwrap();
+        let definitions = &schema["$defs"];
+        assert_eq!(definitions.as_object().unwrap().len(), 125 + 91 + 4);
+        assert_eq!(
+            definitions["Diagnostic"]["required"],
+            json!(["level", "message", "loc"])
+        );
+        assert_eq!(
+            definitions["node.Diagnostic"]["properties"]["type"],
+            json!({ "const": "Diagnostic" })
+        );
+        assert_eq!(
+            definitions["node.Alias"]["properties"]["type"],
+            json!({ "const": "Alias" })
+        );
+        assert_eq!(
+            definitions["DiagnosticMessage"]["oneOf"][90],
+            json!({ "$ref": "#/$defs/message.Alias" })
+        );
+    }
+}
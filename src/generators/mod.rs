@@ -0,0 +1,367 @@
+//! Built-in generators of schemas and type declarations for other languages.
+//!
+//! Text formats are Liquid templates shipped with this crate (JSON ones are built with `serde_json`),
+//! all of them are rendered for a given [`Defs`] (use [`Defs::builtin`] for built-in nodes and messages).
+
+use crate::{Defs, LiquidTemplate};
+
+mod ast_json_schema;
+mod proto_numbers;
+mod tree_sitter;
+
+fn render(src: &str, defs: &Defs) -> String {
+    render_with(LiquidTemplate::new_eval(src), defs)
+}
+
+fn render_with(template: LiquidTemplate, defs: &Defs) -> String {
+    template.with_defs(defs).render()
+}
+
+/// Attribute of a node used as a value of its `type` discriminant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TypeTag {
+    /// `wqp_name`, like `"and_asgn"`.
+    ///
+    /// Not unique (`If`, `IfMod` and `IfTernary` are all `"if"`), so it doesn't discriminate all nodes.
+    WqpName,
+    /// `camelcase_name`, like `"AndAsgn"`, unique for every node.
+    #[default]
+    CamelcaseName,
+}
+
+impl TypeTag {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "wqp_name" => Some(Self::WqpName),
+            "camelcase_name" => Some(Self::CamelcaseName),
+            _ => None,
+        }
+    }
+
+    fn attribute(self) -> &'static str {
+        match self {
+            Self::WqpName => "wqp_name",
+            Self::CamelcaseName => "camelcase_name",
+        }
+    }
+}
+
+/// Protocol Buffers (proto3) schema: a message per node/diagnostic message,
+/// `Node`/`DiagnosticMessage` wrappers with a `oneof` of all variants, `Loc` and `Diagnostic`.
+///
+/// Numbers of built-in nodes, messages and fields are fixed in `src/generators/protobuf_numbers.json`,
+/// so adding a node or a field doesn't renumber existing ones. Custom nodes and fields need
+/// a `proto_number` [annotation](crate::overlay), it also overrides a built-in number.
+///
+/// Returns an error if a number is missing, invalid or duplicated within a message or a `oneof`,
+/// or if a node or message name clashes with another type (including `Loc`, `Node`, `ErrorLevel`,
+/// `Diagnostic` and `DiagnosticMessage`) or its `oneof` field name with another variant.
+pub fn protobuf(defs: &Defs) -> Result<String, String> {
+    proto_numbers::check_names(defs)?;
+    let defs = proto_numbers::with_numbers(defs)?;
+    Ok(render(include_str!("protobuf.proto.liquid"), &defs))
+}
+
+/// FlatBuffers schema: a table per node/diagnostic message, `Node`/`DiagnosticMessage` unions,
+/// `Loc` struct and `ParserResult` root table.
+///
+/// Lists of nodes are vectors of `AnyNode` tables, union order follows the order of nodes/messages.
+pub fn flatbuffers(defs: &Defs) -> String {
+    render(include_str!("flatbuffers.fbs.liquid"), defs)
+}
+
+/// TypeScript declarations (`.d.ts`): an interface per node with JSDoc comments
+/// and a `Node` union discriminated by `type` property.
+///
+/// Only [`TypeTag::CamelcaseName`] (the default) makes it a discriminated union,
+/// with [`TypeTag::WqpName`] some interfaces share the same `type`.
+///
+/// Optional fields (`MaybeNode`, `MaybeLoc`, ...) are declared as `field?: T | null`.
+pub fn typescript(defs: &Defs, tag: TypeTag) -> String {
+    let template = LiquidTemplate::new_eval(include_str!("typescript.d.ts.liquid"))
+        .with_global("tag", liquid_core::Value::scalar(tag.attribute()));
+    render_with(template, defs)
+}
+
+/// RBS signatures of Ruby classes of nodes: `Nodes::<Name> < Node` with a reader per field,
+/// defined in a given `namespace` (like `LibRubyParser`).
+pub fn rbs(defs: &Defs, namespace: &str) -> String {
+    let template = LiquidTemplate::new_eval(include_str!("rbs.rbs.liquid")).with_global(
+        "namespace",
+        liquid_core::Value::scalar(namespace.to_owned()),
+    );
+    render_with(template, defs)
+}
+
+/// Sorbet RBI signatures, same classes as [`rbs`].
+pub fn rbi(defs: &Defs, namespace: &str) -> String {
+    let template = LiquidTemplate::new_eval(include_str!("rbi.rbi.liquid")).with_global(
+        "namespace",
+        liquid_core::Value::scalar(namespace.to_owned()),
+    );
+    render_with(template, defs)
+}
+
+/// Python module with a `dataclass` per node and per diagnostic message (docstrings from `comment`),
+/// `Loc` and `Node`/`DiagnosticMessage` unions.
+///
+/// Names that are Python keywords get `_` suffix (`True_`, `from_`).
+pub fn python(defs: &Defs) -> String {
+    render(include_str!("python.py.liquid"), defs)
+}
+
+/// Python stubs (`.pyi`), same classes as [`python`] without docstrings.
+pub fn python_stub(defs: &Defs) -> String {
+    render(include_str!("python.pyi.liquid"), defs)
+}
+
+/// `node-types.json` in tree-sitter format: a named type per node (`wqp_name`)
+/// with fields holding child nodes, all of them are subtypes of `_node` supertype.
+///
+/// Nodes that share a `wqp_name` (like `If`, `IfMod` and `IfTernary`) are merged into one type.
+pub fn tree_sitter_node_types(defs: &Defs) -> String {
+    tree_sitter::node_types(defs)
+}
+
+/// JSON Schema (draft 2020-12) of a serialized AST: a definition per node and per diagnostic message
+/// (objects with `type` discriminant), `Loc` and `Diagnostic`. Nodes and messages are defined
+/// under `node.<Name>` and `message.<Name>` keys, so their names never clash.
+///
+/// `tag` applies to nodes only, diagnostic messages always use `camelcase_name`.
+/// Use [`TypeTag::CamelcaseName`] (the default): with [`TypeTag::WqpName`] nodes like `If` and `IfMod`
+/// have the same `type`, so a node may match more than one branch of `Node`'s `oneOf`.
+///
+/// Optional fields (`MaybeNode`, `MaybeLoc`, ...) are nullable and not required.
+pub fn ast_json_schema(defs: &Defs, tag: TypeTag) -> String {
+    ast_json_schema::schema(defs, tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TypeTag;
+    use crate::{Defs, Overlay};
+
+    #[test]
+    fn test_protobuf() {
+        let proto = super::protobuf(&Defs::builtin()).unwrap();
+        assert!(proto.contains("syntax = \"proto3\";"));
+        assert!(proto.contains("    Alias alias = 1;\n"));
+        assert!(proto.contains(
+            "// Represents `alias to from` statement.\nmessage Alias {\n  // Target of the `alias`."
+        ), "{}", &proto[..2000]);
+        assert!(proto.contains("  repeated Node statements = 1;\n"));
+        assert!(proto.contains("message UnterminatedHeredoc {\n"));
+        assert_eq!(proto.matches("\nmessage ").count(), 124 + 90 + 4);
+    }
+
+    #[test]
+    fn test_protobuf_numbers() {
+        let overlay = Overlay::from_json(
+            r#"{ "nodes": { "Alias": { "proto_number": 200 }, "Alias.from": { "proto_number": 7 } } }"#,
+        )
+        .unwrap();
+        let proto = super::protobuf(&Defs::builtin().with_overlay(&overlay).unwrap()).unwrap();
+        assert!(proto.contains("    Alias alias = 200;\n"));
+        assert!(proto.contains("  Node from = 7;\n"));
+    }
+
+    // Numbers are part of the wire format, they must not change when nodes or fields are added
+    #[test]
+    fn test_protobuf_numbers_are_stable() {
+        let mut defs = Defs::builtin();
+        let mut node = crate::NodeDef {
+            camelcase_name: String::from("Aaa"),
+            wqp_name: String::from("aaa"),
+            ..Default::default()
+        };
+        node.annotations
+            .insert(String::from("proto_number"), 1000.into());
+        defs.nodes.insert(0, node);
+        let mut field = defs.nodes[1].fields[0].clone();
+        field.snakecase_name = String::from("aaa");
+        field
+            .annotations
+            .insert(String::from("proto_number"), 100.into());
+        defs.nodes[1].fields.insert(0, field);
+
+        let proto = super::protobuf(&defs).unwrap();
+        for expected in [
+            "    Aaa aaa = 1000;\n",
+            "    Alias alias = 1;\n",
+            "    Send send = 107;\n",
+            "    ZSuper z_super = 124;\n",
+            "  Node aaa = 100;\n",
+            "  Node to = 1;\n",
+            "  Node from = 2;\n",
+            "    UnterminatedHeredoc unterminated_heredoc = 15;\n",
+            "  string heredoc_id = 1;\n",
+        ] {
+            assert!(proto.contains(expected), "{}", expected);
+        }
+    }
+
+    #[test]
+    fn test_protobuf_number_errors() {
+        let mut defs = Defs::builtin();
+        defs.nodes.push(crate::NodeDef {
+            camelcase_name: String::from("Custom"),
+            wqp_name: String::from("custom"),
+            ..Default::default()
+        });
+        assert_eq!(
+            super::protobuf(&defs).unwrap_err(),
+            "Custom has no protobuf number, set a `proto_number` annotation"
+        );
+
+        let overlay =
+            Overlay::from_json(r#"{ "nodes": { "Alias.from": { "proto_number": 1 } } }"#).unwrap();
+        assert_eq!(
+            super::protobuf(&Defs::builtin().with_overlay(&overlay).unwrap()).unwrap_err(),
+            "protobuf number 1 is used by both Alias.to and Alias.from in message Alias"
+        );
+
+        let overlay =
+            Overlay::from_json(r#"{ "messages": { "AliasNthRef": { "proto_number": 1 } } }"#)
+                .unwrap();
+        assert!(
+            super::protobuf(&Defs::builtin().with_overlay(&overlay).unwrap())
+                .unwrap_err()
+                .ends_with("in oneof DiagnosticMessage.message")
+        );
+
+        let overlay =
+            Overlay::from_json(r#"{ "nodes": { "Alias": { "proto_number": 19000 } } }"#).unwrap();
+        assert_eq!(
+            super::protobuf(&Defs::builtin().with_overlay(&overlay).unwrap()).unwrap_err(),
+            "protobuf number 19000 of Alias is not allowed"
+        );
+    }
+
+    #[test]
+    fn test_protobuf_name_errors() {
+        let node = |name: &str| crate::NodeDef {
+            camelcase_name: String::from(name),
+            wqp_name: String::from("custom"),
+            ..Default::default()
+        };
+
+        let mut defs = Defs::builtin();
+        defs.nodes.push(node("Diagnostic"));
+        assert_eq!(
+            super::protobuf(&defs).unwrap_err(),
+            "protobuf type Diagnostic is declared by both built-in Diagnostic and node Diagnostic"
+        );
+
+        let mut defs = Defs::builtin();
+        let name = defs.messages[0].camelcase_name.clone();
+        defs.nodes.push(node(&name));
+        assert_eq!(
+            super::protobuf(&defs).unwrap_err(),
+            format!(
+                "protobuf type {} is declared by both node {} and message {}",
+                name, name, name
+            )
+        );
+
+        let mut defs = Defs::builtin();
+        defs.nodes.push(node("Z_super"));
+        assert_eq!(
+            super::protobuf(&defs).unwrap_err(),
+            "protobuf field z_super is declared by both node ZSuper and node Z_super in oneof Node.node"
+        );
+    }
+
+    #[test]
+    fn test_flatbuffers() {
+        let fbs = super::flatbuffers(&Defs::builtin());
+        assert!(fbs.contains("namespace lib_ruby_parser.fbs;"));
+        assert!(fbs.contains("union Node {\n  Alias,\n  And,\n"));
+        assert!(fbs.contains("  ZSuper\n}\n"));
+        assert!(fbs.contains("  statements: [AnyNode];\n"));
+        assert!(fbs.contains("  keyword_l: Loc;\n"));
+        assert!(fbs.contains(
+            "table UnterminatedHeredoc {\n  // Heredoc identifier\n  heredoc_id: string;\n}"
+        ));
+        assert!(fbs.ends_with("root_type ParserResult;\n"));
+        assert_eq!(fbs.matches("\ntable ").count(), 124 + 90 + 3);
+    }
+
+    #[test]
+    fn test_typescript() {
+        let dts = super::typescript(&Defs::builtin(), TypeTag::WqpName);
+        assert!(dts.contains("export type Node =\n  | Alias\n  | And\n"));
+        assert!(dts.contains("  | ZSuper;\n"));
+        assert!(dts.contains(
+            "/**\n * Represents `alias to from` statement.\n */\nexport interface Alias {\n  type: \"alias\";\n"
+        ), "{}", &dts[..3000]);
+        assert!(dts.contains("export interface AndAsgn {\n  type: \"and_asgn\";\n"));
+        assert!(dts.contains("  else_l?: Loc | null;\n"));
+        assert!(dts.contains("  statements: Node[];\n"));
+
+        let dts = super::typescript(&Defs::builtin(), TypeTag::default());
+        assert!(dts.contains("export interface AndAsgn {\n  type: \"AndAsgn\";\n"));
+
+        let mut tags = dts
+            .lines()
+            .filter(|line| line.starts_with("  type: "))
+            .collect::<Vec<_>>();
+        assert_eq!(tags.len(), 124);
+        tags.sort_unstable();
+        tags.dedup();
+        assert_eq!(tags.len(), 124);
+    }
+
+    #[test]
+    fn test_rbs() {
+        let rbs = super::rbs(&Defs::builtin(), "LibRubyParser");
+        assert!(rbs.starts_with(
+            "# Generated by lib-ruby-parser-nodes, do not edit.\n\nmodule LibRubyParser\n"
+        ));
+        assert!(rbs.contains(
+            "    # Represents <tt>alias to from</tt> statement.\n    class Alias < Node\n      # Target of the +alias+.\n"
+        ));
+        assert!(rbs.contains("      def to: () -> Node\n"));
+        assert!(rbs.contains("      def statements: () -> ::Array[Node]\n"));
+        assert!(rbs.contains("      def name: () -> ::String\n"));
+        assert!(!rbs.contains(" Array["));
+        assert!(!rbs.contains("> String"));
+        assert!(rbs.contains("      def else_l: () -> Loc?\n"));
+        assert_eq!(rbs.matches(" < Node\n").count(), 124);
+    }
+
+    #[test]
+    fn test_rbi() {
+        let rbi = super::rbi(&Defs::builtin(), "LibRubyParser");
+        assert!(rbi.starts_with("# typed: strict\n"));
+        assert!(rbi.contains("      sig { returns(Node) }\n      def to; end\n"));
+        assert!(rbi.contains("      sig { returns(T.nilable(Loc)) }\n      def else_l; end\n"));
+        assert!(rbi.contains("      sig { returns(::String) }\n      def name; end\n"));
+        assert_eq!(rbi.matches(" < Node\n").count(), 124);
+    }
+
+    #[test]
+    fn test_python() {
+        let py = super::python(&Defs::builtin());
+        assert!(py.contains(
+            "@dataclass\nclass Alias:\n    r\"\"\"\n    Represents `alias to from` statement.\n    \"\"\"\n\n    to: Node\n    r\"\"\"\n    Target of the `alias`.\n\n"
+        ));
+        assert!(py.contains("    from_: Node\n"));
+        assert!(py.contains("\nclass True_:\n"));
+        assert!(py.contains("    statements: List[Node]\n"));
+        assert!(py.contains("\nclass Str:\n"));
+        assert!(py.contains("    value: str\n"));
+        assert!(!py.contains("bytes"));
+        assert!(py.contains("    else_l: Optional[Loc]\n"));
+        assert!(py.contains("Node = Union[\n    Alias,\n"));
+        assert_eq!(py.matches("@dataclass\n").count(), 1 + 124 + 90);
+    }
+
+    #[test]
+    fn test_python_stub() {
+        let pyi = super::python_stub(&Defs::builtin());
+        assert!(pyi.contains("@dataclass\nclass Alias:\n    to: Node\n    from_: Node\n"));
+        assert!(pyi.contains("@dataclass\nclass FractionAfterNumeric:\n    ...\n"));
+        assert!(!pyi.contains("\"\"\""));
+        assert_eq!(pyi.matches("@dataclass\n").count(), 1 + 124 + 90);
+    }
+}
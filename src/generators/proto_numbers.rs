@@ -0,0 +1,154 @@
+// Field numbers of the protobuf schema.
+//
+// Numbers of built-in nodes, messages and their fields are stored in `protobuf_numbers.json`,
+// so adding a node or a field never changes numbers of existing ones.
+// Keys are the same as in an overlay (`Send`, `Send.method_name`).
+//
+// Names are checked here too: nodes and messages share one package with the built-in types.
+
+use crate::defs::{Annotations, Defs};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+#[derive(Deserialize)]
+struct Registry {
+    nodes: BTreeMap<String, u64>,
+    messages: BTreeMap<String, u64>,
+}
+
+// Top-level types declared by `protobuf.proto.liquid` itself
+const BUILTIN_TYPES: &[&str] = &[
+    "Loc",
+    "Node",
+    "ErrorLevel",
+    "Diagnostic",
+    "DiagnosticMessage",
+];
+
+const MAX_NUMBER: u64 = (1 << 29) - 1;
+const RESERVED_NUMBERS: std::ops::RangeInclusive<u64> = 19_000..=19_999;
+
+// Sets `proto_number` annotation of every node, message and field,
+// explicit annotations take precedence over the registry.
+pub(crate) fn with_numbers(defs: &Defs) -> Result<Defs, String> {
+    let registry: Registry = serde_json::from_str(include_str!("protobuf_numbers.json"))
+        .map_err(|e| format!("invalid protobuf_numbers.json: {}", e))?;
+    let mut defs = defs.clone();
+
+    assign(
+        "oneof Node.node",
+        defs.nodes
+            .iter_mut()
+            .map(|node| (node.camelcase_name.clone(), &mut node.annotations)),
+        &registry.nodes,
+    )?;
+    for node in defs.nodes.iter_mut() {
+        assign(
+            &format!("message {}", node.camelcase_name),
+            node.fields.iter_mut().map(|field| {
+                let key = format!("{}.{}", node.camelcase_name, field.snakecase_name);
+                (key, &mut field.annotations)
+            }),
+            &registry.nodes,
+        )?;
+    }
+
+    assign(
+        "oneof DiagnosticMessage.message",
+        defs.messages
+            .iter_mut()
+            .map(|message| (message.camelcase_name.clone(), &mut message.annotations)),
+        &registry.messages,
+    )?;
+    for message in defs.messages.iter_mut() {
+        assign(
+            &format!("message {}", message.camelcase_name),
+            message.fields.iter_mut().map(|field| {
+                let key = format!("{}.{}", message.camelcase_name, field.snakecase_name);
+                (key, &mut field.annotations)
+            }),
+            &registry.messages,
+        )?;
+    }
+
+    Ok(defs)
+}
+
+// Rejects nodes and messages whose type name or `oneof` field name is already taken.
+pub(crate) fn check_names(defs: &Defs) -> Result<(), String> {
+    let mut types = BTreeMap::<String, String>::new();
+    for name in BUILTIN_TYPES {
+        types.insert(name.to_string(), format!("built-in {}", name));
+    }
+    let nodes = defs.nodes.iter().map(|node| {
+        let name = node.camelcase_name.clone();
+        (
+            "oneof Node.node",
+            format!("node {}", name),
+            name,
+            node.lower_name(),
+        )
+    });
+    let messages = defs.messages.iter().map(|message| {
+        let name = message.camelcase_name.clone();
+        let scope = "oneof DiagnosticMessage.message";
+        (
+            scope,
+            format!("message {}", name),
+            name,
+            message.lower_name(),
+        )
+    });
+
+    let mut fields = BTreeMap::<(&str, String), String>::new();
+    for (scope, owner, name, field) in nodes.chain(messages) {
+        if let Some(other) = types.insert(name.clone(), owner.clone()) {
+            return Err(format!(
+                "protobuf type {} is declared by both {} and {}",
+                name, other, owner
+            ));
+        }
+        if let Some(other) = fields.insert((scope, field.clone()), owner.clone()) {
+            return Err(format!(
+                "protobuf field {} is declared by both {} and {} in {}",
+                field, other, owner, scope
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn assign<'a>(
+    scope: &str,
+    entries: impl Iterator<Item = (String, &'a mut Annotations)>,
+    registry: &BTreeMap<String, u64>,
+) -> Result<(), String> {
+    let mut used = BTreeMap::<u64, String>::new();
+    for (key, annotations) in entries {
+        let number = match annotations.get("proto_number") {
+            Some(value) => value
+                .as_u64()
+                .ok_or_else(|| format!("proto_number of {} must be a positive integer", key))?,
+            None => *registry.get(&key).ok_or_else(|| {
+                format!(
+                    "{} has no protobuf number, set a `proto_number` annotation",
+                    key
+                )
+            })?,
+        };
+        if number == 0 || number > MAX_NUMBER || RESERVED_NUMBERS.contains(&number) {
+            return Err(format!(
+                "protobuf number {} of {} is not allowed",
+                number, key
+            ));
+        }
+        if let Some(other) = used.insert(number, key.clone()) {
+            return Err(format!(
+                "protobuf number {} is used by both {} and {} in {}",
+                number, other, key, scope
+            ));
+        }
+        annotations.insert(String::from("proto_number"), number.into());
+    }
+    Ok(())
+}
@@ -0,0 +1,200 @@
+// `node-types.json` in tree-sitter format.
+//
+// All nodes are subtypes of `_node` supertype, fields hold child nodes only
+// (locations and scalar values are not children in tree-sitter terms).
+//
+// Types are named by `wqp_name` that is shared by some nodes (`If`, `IfMod` and `IfTernary` are all `if`),
+// such nodes are merged into one type with a union of their fields.
+
+use crate::defs::{Defs, NodeDef};
+use crate::NodeFieldType;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+const SUPERTYPE: &str = "_node";
+
+#[derive(Serialize)]
+struct NodeType {
+    #[serde(rename = "type")]
+    type_: String,
+    named: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fields: Option<BTreeMap<String, FieldInfo>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    subtypes: Option<Vec<TypeRef>>,
+}
+
+#[derive(Serialize)]
+struct FieldInfo {
+    multiple: bool,
+    required: bool,
+    types: Vec<TypeRef>,
+}
+
+#[derive(Serialize)]
+struct TypeRef {
+    #[serde(rename = "type")]
+    type_: String,
+    named: bool,
+}
+
+impl TypeRef {
+    fn named(type_: &str) -> Self {
+        Self {
+            type_: type_.to_string(),
+            named: true,
+        }
+    }
+}
+
+fn node_fields(node: &NodeDef) -> BTreeMap<String, FieldInfo> {
+    node.fields
+        .iter()
+        .filter(|field| field.field_type.is_node())
+        .map(|field| {
+            let info = FieldInfo {
+                multiple: field.field_type == NodeFieldType::Nodes,
+                required: field.field_type == NodeFieldType::Node,
+                types: vec![TypeRef::named(SUPERTYPE)],
+            };
+            (field.snakecase_name.clone(), info)
+        })
+        .collect()
+}
+
+// A field is required only if every node of the type requires it
+fn merge_fields(
+    mut fields: BTreeMap<String, FieldInfo>,
+    other: BTreeMap<String, FieldInfo>,
+) -> BTreeMap<String, FieldInfo> {
+    for (name, info) in fields.iter_mut() {
+        match other.get(name) {
+            Some(other) => {
+                info.multiple |= other.multiple;
+                info.required &= other.required;
+            }
+            None => info.required = false,
+        }
+    }
+    for (name, info) in other {
+        fields.entry(name).or_insert(FieldInfo {
+            required: false,
+            ..info
+        });
+    }
+    fields
+}
+
+// Nodes grouped by `wqp_name` in order of their first occurrence
+fn node_groups(defs: &Defs) -> Vec<(&str, Vec<&NodeDef>)> {
+    let mut groups: Vec<(&str, Vec<&NodeDef>)> = vec![];
+    for node in defs.nodes.iter() {
+        match groups.iter_mut().find(|(name, _)| *name == node.wqp_name) {
+            Some((_, nodes)) => nodes.push(node),
+            None => groups.push((&node.wqp_name, vec![node])),
+        }
+    }
+    groups
+}
+
+fn node_type(wqp_name: &str, nodes: &[&NodeDef]) -> NodeType {
+    let (first, rest) = nodes.split_first().expect("groups are never empty");
+    let fields = rest.iter().fold(node_fields(first), |fields, node| {
+        merge_fields(fields, node_fields(node))
+    });
+
+    NodeType {
+        type_: wqp_name.to_string(),
+        named: true,
+        fields: Some(fields),
+        subtypes: None,
+    }
+}
+
+pub(crate) fn node_types(defs: &Defs) -> String {
+    let groups = node_groups(defs);
+
+    let supertype = NodeType {
+        type_: SUPERTYPE.to_string(),
+        named: true,
+        fields: None,
+        subtypes: Some(
+            groups
+                .iter()
+                .map(|(wqp_name, _)| TypeRef::named(wqp_name))
+                .collect(),
+        ),
+    };
+
+    let node_types = std::iter::once(supertype)
+        .chain(
+            groups
+                .iter()
+                .map(|(wqp_name, nodes)| node_type(wqp_name, nodes)),
+        )
+        .collect::<Vec<_>>();
+
+    serde_json::to_string_pretty(&node_types).expect("node types are always serializable")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Defs;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_node_types() {
+        let json: Value = serde_json::from_str(&super::node_types(&Defs::builtin())).unwrap();
+        let types = json.as_array().unwrap();
+        // 124 nodes, `IfMod`/`IfTernary`, `Heredoc` and `XHeredoc` share types with other nodes
+        assert_eq!(types.len(), 1 + 120);
+
+        assert_eq!(types[0]["type"], "_node");
+        assert_eq!(types[0]["subtypes"].as_array().unwrap().len(), 120);
+        assert_eq!(
+            types[0]["subtypes"][0],
+            json!({ "type": "alias", "named": true })
+        );
+
+        assert_eq!(
+            types[1],
+            json!({
+                "type": "alias",
+                "named": true,
+                "fields": {
+                    "from": { "multiple": false, "required": true, "types": [{ "type": "_node", "named": true }] },
+                    "to": { "multiple": false, "required": true, "types": [{ "type": "_node", "named": true }] }
+                }
+            })
+        );
+
+        let begin = types.iter().find(|t| t["type"] == "begin").unwrap();
+        assert_eq!(begin["fields"]["statements"]["multiple"], true);
+        assert_eq!(begin["fields"]["statements"]["required"], false);
+
+        let nil = types.iter().find(|t| t["type"] == "nil").unwrap();
+        assert_eq!(nil["fields"], json!({}));
+    }
+
+    #[test]
+    fn test_shared_wqp_names() {
+        let json: Value = serde_json::from_str(&super::node_types(&Defs::builtin())).unwrap();
+        let types = json.as_array().unwrap();
+
+        let mut names = types
+            .iter()
+            .map(|t| t["type"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        let count = names.len();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), count);
+        assert_eq!(types[0]["subtypes"].as_array().unwrap().len(), count - 1);
+
+        // `If` (optional branches), `IfMod` (optional branches) and `IfTernary` (required branches)
+        let if_ = types.iter().find(|t| t["type"] == "if").unwrap();
+        assert_eq!(if_["fields"]["cond"]["required"], true);
+        assert_eq!(if_["fields"]["if_true"]["required"], false);
+        assert_eq!(if_["fields"]["if_false"]["required"], false);
+    }
+}
@@ -0,0 +1,230 @@
+// `nodes` and `messages` globals of templates.
+//
+// Templates see serialized objects, so computed attributes (`upper_name`, `index`, ...)
+// are added here on top of the plain `NodeDef`/`MessageDef` fields.
+//
+// Fields also get a back-reference to their owner (`field.node`/`field.message`),
+// it has all attributes of the owner except for `fields` to avoid cycles.
+
+use crate::defs::{Annotations, MessageDef, MessageFieldDef, NodeDef, NodeFieldDef};
+use crate::{MessageFieldType, NodeFieldType};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub(crate) struct NodeObject {
+    #[serde(flatten)]
+    summary: NodeSummary,
+    fields: Vec<NodeFieldObject>,
+}
+
+#[derive(Serialize, Clone)]
+struct NodeSummary {
+    camelcase_name: String,
+    wqp_name: String,
+    comment: Vec<String>,
+    annotations: Annotations,
+
+    index: usize,
+    upper_name: String,
+    lower_name: String,
+    fields_count: usize,
+    node_fields_count: usize,
+    loc_fields_count: usize,
+    has_loc_fields: bool,
+}
+
+#[derive(Serialize)]
+pub(crate) struct NodeFieldObject {
+    snakecase_name: String,
+    field_type: NodeFieldType,
+    always_print: bool,
+    comment: Vec<String>,
+    annotations: Annotations,
+
+    is_optional: bool,
+    index: usize,
+    is_last: bool,
+    node: NodeSummary,
+}
+
+#[derive(Serialize)]
+pub(crate) struct MessageObject {
+    #[serde(flatten)]
+    summary: MessageSummary,
+    fields: Vec<MessageFieldObject>,
+}
+
+#[derive(Serialize, Clone)]
+struct MessageSummary {
+    camelcase_name: String,
+    comment: Vec<String>,
+    annotations: Annotations,
+
+    index: usize,
+    upper_name: String,
+    lower_name: String,
+    fields_count: usize,
+}
+
+#[derive(Serialize)]
+pub(crate) struct MessageFieldObject {
+    snakecase_name: String,
+    field_type: MessageFieldType,
+    comment: Vec<String>,
+    annotations: Annotations,
+
+    is_optional: bool,
+    index: usize,
+    is_last: bool,
+    message: MessageSummary,
+}
+
+fn node_object(index: usize, node: &NodeDef) -> NodeObject {
+    let count = |f: fn(&NodeFieldType) -> bool| {
+        node.fields
+            .iter()
+            .filter(|field| f(&field.field_type))
+            .count()
+    };
+
+    let summary = NodeSummary {
+        camelcase_name: node.camelcase_name.clone(),
+        wqp_name: node.wqp_name.clone(),
+        comment: node.comment.clone(),
+        annotations: node.annotations.clone(),
+
+        index,
+        upper_name: node.upper_name(),
+        lower_name: node.lower_name(),
+        fields_count: node.fields.len(),
+        node_fields_count: count(NodeFieldType::is_node),
+        loc_fields_count: count(NodeFieldType::is_loc),
+        has_loc_fields: node.has_loc_fields(),
+    };
+
+    NodeObject {
+        fields: node
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| node_field_object(idx, field, &summary))
+            .collect(),
+        summary,
+    }
+}
+
+fn node_field_object(index: usize, field: &NodeFieldDef, node: &NodeSummary) -> NodeFieldObject {
+    NodeFieldObject {
+        snakecase_name: field.snakecase_name.clone(),
+        field_type: field.field_type.clone(),
+        always_print: field.always_print,
+        comment: field.comment.clone(),
+        annotations: field.annotations.clone(),
+
+        is_optional: field.field_type.is_optional(),
+        index,
+        is_last: index + 1 == node.fields_count,
+        node: node.clone(),
+    }
+}
+
+fn message_object(index: usize, message: &MessageDef) -> MessageObject {
+    let summary = MessageSummary {
+        camelcase_name: message.camelcase_name.clone(),
+        comment: message.comment.clone(),
+        annotations: message.annotations.clone(),
+
+        index,
+        upper_name: message.upper_name(),
+        lower_name: message.lower_name(),
+        fields_count: message.fields.len(),
+    };
+
+    MessageObject {
+        fields: message
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| message_field_object(idx, field, &summary))
+            .collect(),
+        summary,
+    }
+}
+
+fn message_field_object(
+    index: usize,
+    field: &MessageFieldDef,
+    message: &MessageSummary,
+) -> MessageFieldObject {
+    MessageFieldObject {
+        snakecase_name: field.snakecase_name.clone(),
+        field_type: field.field_type.clone(),
+        comment: field.comment.clone(),
+        annotations: field.annotations.clone(),
+
+        is_optional: false,
+        index,
+        is_last: index + 1 == message.fields_count,
+        message: message.clone(),
+    }
+}
+
+pub(crate) fn nodes(nodes: &[NodeDef]) -> Vec<NodeObject> {
+    nodes
+        .iter()
+        .enumerate()
+        .map(|(idx, node)| node_object(idx, node))
+        .collect()
+}
+
+pub(crate) fn messages(messages: &[MessageDef]) -> Vec<MessageObject> {
+    messages
+        .iter()
+        .enumerate()
+        .map(|(idx, message)| message_object(idx, message))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LiquidTemplate;
+
+    #[test]
+    fn test_computed_attributes() {
+        let output = LiquidTemplate::new_eval(
+            "{% assign node = nodes[1] %}{{ node.index }} {{ node.upper_name }} {{ node.lower_name }} {{ node.fields_count }} {{ node.node_fields_count }} {{ node.loc_fields_count }} {{ node.has_loc_fields }}",
+        )
+        .render();
+        assert_eq!(output, "1 AND and 4 2 2 true");
+
+        let output = LiquidTemplate::new_eval(
+            "{% for node in nodes %}{% if node.camelcase_name == 'Def' %}{% for field in node.fields %}{% if field.is_optional %}{{ field.snakecase_name }} {% endif %}{% endfor %}{% endif %}{% endfor %}",
+        )
+        .render();
+        assert_eq!(output, "args body end_l assignment_l ");
+
+        let output = LiquidTemplate::new_eval(
+            "{{ messages[0].index }} {{ messages[0].upper_name }} {{ messages[0].fields_count }}",
+        )
+        .render();
+        assert_eq!(output, "0 FRACTION_AFTER_NUMERIC 0");
+    }
+
+    #[test]
+    fn test_back_references() {
+        let output = LiquidTemplate::new_eval(
+            "{% for field in nodes[0].fields %}{{ field.node.camelcase_name }}.{{ field.snakecase_name }}#{{ field.index }}{% if field.is_last %};{% else %}, {% endif %}{% endfor %}",
+        )
+        .render();
+        assert_eq!(
+            output,
+            "Alias.to#0, Alias.from#1, Alias.keyword_l#2, Alias.expression_l#3;"
+        );
+
+        let output = LiquidTemplate::new_eval(
+            "{% for message in messages %}{% for field in message.fields %}{% if forloop.first %}{{ field.message.camelcase_name }} {{ field.message.index }}{% endif %}{% endfor %}{% endfor %}",
+        )
+        .render();
+        assert!(output.starts_with("UnterminatedHeredoc 14"), "{}", output);
+    }
+}
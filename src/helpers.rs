@@ -1,3 +1,5 @@
+use crate::keywords::{escape_keyword, EscapeStyle, Language};
+
 pub fn camelcase_to_snakecase(s: &str) -> String {
     let mut words = vec![];
     let mut word = String::from("");
@@ -28,18 +30,25 @@ fn test_camelcase_to_snakecase() {
     assert_eq!(camelcase_to_snakecase("F"), "f");
 }
 
-pub fn escape_rust_keyword(s: &str) -> String {
-    match s {
-        "const" | "as" | "else" | "self" | "break" | "false" | "for" | "if" | "return" | "str"
-        | "super" | "true" | "while" | "yield" => format!("{}_", s),
-        _ => s.to_string(),
+fn escape_keyword_or(s: &str, language: Language, extra: &[&str]) -> String {
+    if extra.contains(&s) {
+        format!("{}_", s)
+    } else {
+        escape_keyword(s, language, EscapeStyle::Suffix)
     }
 }
 
+// `str` is not a keyword, but it has always been escaped
+pub fn escape_rust_keyword(s: &str) -> String {
+    escape_keyword_or(s, Language::Rust2021, &["str"])
+}
+
 #[test]
 fn test_escape_rust_keyword() {
     assert_eq!(escape_rust_keyword("foo"), "foo");
     assert_eq!(escape_rust_keyword("while"), "while_");
+    assert_eq!(escape_rust_keyword("type"), "type_");
+    assert_eq!(escape_rust_keyword("str"), "str_");
 }
 
 pub fn snakecase_to_camelcase(s: &str) -> String {
@@ -61,42 +70,32 @@ fn test_snakecase_to_camelcase() {
     assert_eq!(snakecase_to_camelcase("f"), "F");
 }
 
+// C headers are also included from C++, so C++-only `class` and `operator` are escaped too
 pub fn escape_c_keyword(s: &str) -> String {
-    match s {
-        "default" | "operator" | "else" | "const" | "and" | "break" | "case" | "class"
-        | "false" | "float" | "for" | "if" | "int" | "or" | "return" | "true" | "while" => {
-            format!("{}_", s)
-        }
-        _ => s.to_string(),
-    }
+    escape_keyword_or(s, Language::C, &["class", "operator"])
 }
 
 #[test]
 fn test_escape_c_keyword() {
     assert_eq!(escape_c_keyword("foo"), "foo");
     assert_eq!(escape_c_keyword("default"), "default_");
+    assert_eq!(escape_c_keyword("class"), "class_");
 }
 
+// `ERANGE` is a macro from <cerrno>
 pub fn escape_cpp_keyword(s: &str) -> String {
-    match s {
-        "default" | "operator" | "else" | "const" | "and" | "break" | "case" | "class"
-        | "false" | "float" | "for" | "if" | "int" | "or" | "return" | "true" | "while"
-        | "ERANGE" => format!("{}_", s),
-        _ => s.to_string(),
-    }
+    escape_keyword_or(s, Language::Cpp, &["ERANGE"])
 }
 
 #[test]
 fn test_escape_cpp_keyword() {
     assert_eq!(escape_cpp_keyword("foo"), "foo");
     assert_eq!(escape_cpp_keyword("default"), "default_");
+    assert_eq!(escape_cpp_keyword("ERANGE"), "ERANGE_");
 }
 
 pub fn escape_js_keyword(s: &str) -> String {
-    match s {
-        "const" | "default" | "var" | "else" => format!("{}_", s),
-        _ => s.to_string(),
-    }
+    escape_keyword_or(s, Language::JavaScript, &[])
 }
 
 #[test]
@@ -104,3 +103,72 @@ fn test_escape_js_keyword() {
     assert_eq!(escape_js_keyword("var"), "var_");
     assert_eq!(escape_js_keyword("foo"), "foo");
 }
+
+/// Strips trailing whitespace from every line, removes leading/trailing blank lines
+/// and collapses consecutive blank lines into one.
+///
+/// Trailing newline is kept if the input has one.
+pub fn normalize_whitespace(s: &str) -> String {
+    let mut lines: Vec<&str> = vec![];
+    for line in s.lines().map(str::trim_end) {
+        let prev_is_blank = lines.last().map(|prev| prev.is_empty()).unwrap_or(true);
+        if line.is_empty() && prev_is_blank {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last() == Some(&"") {
+        lines.pop();
+    }
+
+    let mut out = lines.join("\n");
+    if s.ends_with('\n') && !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+#[test]
+fn test_normalize_whitespace() {
+    assert_eq!(
+        normalize_whitespace("\n\nfoo  \n\n\n\tbar\n\n"),
+        "foo\n\n\tbar\n"
+    );
+    assert_eq!(normalize_whitespace("foo\n \nbar"), "foo\n\nbar");
+    assert_eq!(normalize_whitespace("\n\n"), "");
+}
+
+pub fn render_comment<S: AsRef<str>>(lines: &[S], prefix: &str, offset: usize) -> String {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| {
+            let mut line = line.as_ref().to_string();
+            if !line.is_empty() {
+                line = format!(" {}", line);
+            }
+            let spaces = if idx == 0 {
+                // Do not add prefix to the first line
+                String::from("")
+            } else {
+                " ".repeat(offset)
+            };
+            format!(
+                "{spaces}{prefix}{line}",
+                spaces = spaces,
+                prefix = prefix,
+                line = line
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_render_comment() {
+    assert_eq!(
+        render_comment(&["foo", "", "bar"], "//", 4),
+        "// foo\n    //\n    // bar"
+    );
+    assert_eq!(render_comment::<&str>(&[], "//", 4), "");
+}
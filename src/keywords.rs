@@ -0,0 +1,800 @@
+//! Reserved words of target languages and escaping of identifiers that clash with them.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust2015,
+    Rust2018,
+    Rust2021,
+    C,
+    Cpp,
+    JavaScript,
+    TypeScript,
+    Python,
+    Go,
+    Java,
+    Kotlin,
+    Swift,
+    CSharp,
+    Ruby,
+}
+
+impl Language {
+    pub fn from_name(name: &str) -> Option<Self> {
+        let language = match name {
+            "rust2015" => Self::Rust2015,
+            "rust2018" => Self::Rust2018,
+            "rust" | "rust2021" => Self::Rust2021,
+            "c" => Self::C,
+            "cpp" | "c++" => Self::Cpp,
+            "js" | "javascript" => Self::JavaScript,
+            "ts" | "typescript" => Self::TypeScript,
+            "python" | "py" => Self::Python,
+            "go" => Self::Go,
+            "java" => Self::Java,
+            "kotlin" | "kt" => Self::Kotlin,
+            "swift" => Self::Swift,
+            "csharp" | "cs" | "c#" => Self::CSharp,
+            "ruby" | "rb" => Self::Ruby,
+            _ => return None,
+        };
+        Some(language)
+    }
+
+    /// All reserved words of the language (keywords, reserved-for-future-use words
+    /// and literals like `true`/`null` that can't be used as identifiers).
+    pub fn reserved_words(self) -> &'static [&'static str] {
+        match self {
+            Self::Rust2015 => RUST_2015,
+            Self::Rust2018 | Self::Rust2021 => RUST_2018,
+            Self::C => C11,
+            Self::Cpp => CPP20,
+            Self::JavaScript => JAVASCRIPT,
+            Self::TypeScript => TYPESCRIPT,
+            Self::Python => PYTHON,
+            Self::Go => GO,
+            Self::Java => JAVA,
+            Self::Kotlin => KOTLIN,
+            Self::Swift => SWIFT,
+            Self::CSharp => CSHARP,
+            Self::Ruby => RUBY,
+        }
+    }
+
+    pub fn is_reserved(self, s: &str) -> bool {
+        self.reserved_words().contains(&s) || (self == Self::TypeScript && JAVASCRIPT.contains(&s))
+    }
+
+    /// Returns `s` written as a raw identifier (like `r#type` in Rust or `@class` in C#),
+    /// or `None` if the language has no raw identifiers or `s` can't be one.
+    pub fn raw_identifier(self, s: &str) -> Option<String> {
+        match self {
+            Self::Rust2015 | Self::Rust2018 | Self::Rust2021 => match s {
+                "crate" | "self" | "Self" | "super" => None,
+                _ => Some(format!("r#{}", s)),
+            },
+            Self::Kotlin | Self::Swift => Some(format!("`{}`", s)),
+            Self::CSharp => Some(format!("@{}", s)),
+            _ => None,
+        }
+    }
+
+    pub fn supports_raw_identifiers(self) -> bool {
+        matches!(
+            self,
+            Self::Rust2015
+                | Self::Rust2018
+                | Self::Rust2021
+                | Self::Kotlin
+                | Self::Swift
+                | Self::CSharp
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscapeStyle {
+    /// `type` -> `type_`
+    Suffix,
+    /// `type` -> `_type`
+    Prefix,
+    /// `type` -> `r#type` (Rust), `@type` (C#), `` `type` `` (Kotlin, Swift).
+    ///
+    /// Falls back to `Suffix` for words that can't be raw identifiers (like `self` in Rust).
+    Raw,
+}
+
+impl EscapeStyle {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "suffix" => Some(Self::Suffix),
+            "prefix" => Some(Self::Prefix),
+            "raw" => Some(Self::Raw),
+            _ => None,
+        }
+    }
+}
+
+pub fn escape_keyword(s: &str, language: Language, style: EscapeStyle) -> String {
+    if !language.is_reserved(s) {
+        return s.to_string();
+    }
+
+    match style {
+        EscapeStyle::Suffix => format!("{}_", s),
+        EscapeStyle::Prefix => format!("_{}", s),
+        EscapeStyle::Raw => language
+            .raw_identifier(s)
+            .unwrap_or_else(|| format!("{}_", s)),
+    }
+}
+
+/// Same as [`escape_keyword`], but language and style are given by name
+/// (like `"rust"`, `"raw"`), used by template filters.
+pub fn escape_keyword_by_name(
+    s: &str,
+    language: &str,
+    style: Option<&str>,
+) -> Result<String, String> {
+    let lang =
+        Language::from_name(language).ok_or_else(|| format!("unknown language {}", language))?;
+
+    let style = match style {
+        Some(style) => EscapeStyle::from_name(style)
+            .ok_or_else(|| format!("unknown escape style {}", style))?,
+        None => EscapeStyle::Suffix,
+    };
+
+    if style == EscapeStyle::Raw && !lang.supports_raw_identifiers() {
+        return Err(format!("{} has no raw identifiers", language));
+    }
+
+    Ok(escape_keyword(s, lang, style))
+}
+
+// Strict and reserved keywords, https://doc.rust-lang.org/reference/keywords.html
+static RUST_2015: &[&str] = &[
+    "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn", "for",
+    "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+    "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where",
+    "while", "abstract", "become", "box", "do", "final", "macro", "override", "priv", "typeof",
+    "unsized", "virtual", "yield",
+];
+
+// 2018 edition adds `async`, `await`, `dyn` and `try`, 2021 edition adds no new keywords
+static RUST_2018: &[&str] = &[
+    "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn", "for",
+    "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+    "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where",
+    "while", "async", "await", "dyn", "abstract", "become", "box", "do", "final", "macro",
+    "override", "priv", "typeof", "unsized", "virtual", "yield", "try",
+];
+
+// C11 keywords plus macros from <stdbool.h> and <iso646.h>
+static C11: &[&str] = &[
+    "auto",
+    "break",
+    "case",
+    "char",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extern",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "register",
+    "restrict",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "struct",
+    "switch",
+    "typedef",
+    "union",
+    "unsigned",
+    "void",
+    "volatile",
+    "while",
+    "_Alignas",
+    "_Alignof",
+    "_Atomic",
+    "_Bool",
+    "_Complex",
+    "_Generic",
+    "_Imaginary",
+    "_Noreturn",
+    "_Static_assert",
+    "_Thread_local",
+    "bool",
+    "true",
+    "false",
+    "and",
+    "and_eq",
+    "bitand",
+    "bitor",
+    "compl",
+    "not",
+    "not_eq",
+    "or",
+    "or_eq",
+    "xor",
+    "xor_eq",
+];
+
+// https://en.cppreference.com/w/cpp/keyword
+static CPP20: &[&str] = &[
+    "alignas",
+    "alignof",
+    "and",
+    "and_eq",
+    "asm",
+    "auto",
+    "bitand",
+    "bitor",
+    "bool",
+    "break",
+    "case",
+    "catch",
+    "char",
+    "char8_t",
+    "char16_t",
+    "char32_t",
+    "class",
+    "compl",
+    "concept",
+    "const",
+    "consteval",
+    "constexpr",
+    "constinit",
+    "const_cast",
+    "continue",
+    "co_await",
+    "co_return",
+    "co_yield",
+    "decltype",
+    "default",
+    "delete",
+    "do",
+    "double",
+    "dynamic_cast",
+    "else",
+    "enum",
+    "explicit",
+    "export",
+    "extern",
+    "false",
+    "float",
+    "for",
+    "friend",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "mutable",
+    "namespace",
+    "new",
+    "noexcept",
+    "not",
+    "not_eq",
+    "nullptr",
+    "operator",
+    "or",
+    "or_eq",
+    "private",
+    "protected",
+    "public",
+    "register",
+    "reinterpret_cast",
+    "requires",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "static_assert",
+    "static_cast",
+    "struct",
+    "switch",
+    "template",
+    "this",
+    "thread_local",
+    "throw",
+    "true",
+    "try",
+    "typedef",
+    "typeid",
+    "typename",
+    "union",
+    "unsigned",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "wchar_t",
+    "while",
+    "xor",
+    "xor_eq",
+];
+
+// Reserved words of ES2015+ including strict mode ones
+static JAVASCRIPT: &[&str] = &[
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+// Contextual keywords and built-in type names of TypeScript, in addition to JAVASCRIPT
+static TYPESCRIPT: &[&str] = &[
+    "abstract",
+    "accessor",
+    "any",
+    "as",
+    "asserts",
+    "async",
+    "bigint",
+    "boolean",
+    "constructor",
+    "declare",
+    "from",
+    "get",
+    "global",
+    "infer",
+    "is",
+    "keyof",
+    "module",
+    "namespace",
+    "never",
+    "number",
+    "object",
+    "of",
+    "out",
+    "override",
+    "readonly",
+    "require",
+    "satisfies",
+    "set",
+    "string",
+    "symbol",
+    "type",
+    "undefined",
+    "unique",
+    "unknown",
+];
+
+static PYTHON: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+static GO: &[&str] = &[
+    "break",
+    "case",
+    "chan",
+    "const",
+    "continue",
+    "default",
+    "defer",
+    "else",
+    "fallthrough",
+    "for",
+    "func",
+    "go",
+    "goto",
+    "if",
+    "import",
+    "interface",
+    "map",
+    "package",
+    "range",
+    "return",
+    "select",
+    "struct",
+    "switch",
+    "type",
+    "var",
+];
+
+// Keywords, literals and restricted identifiers
+static JAVA: &[&str] = &[
+    "abstract",
+    "assert",
+    "boolean",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "implements",
+    "import",
+    "instanceof",
+    "int",
+    "interface",
+    "long",
+    "native",
+    "new",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "strictfp",
+    "super",
+    "switch",
+    "synchronized",
+    "this",
+    "throw",
+    "throws",
+    "transient",
+    "try",
+    "void",
+    "volatile",
+    "while",
+    "true",
+    "false",
+    "null",
+    "_",
+    "var",
+    "yield",
+    "record",
+];
+
+// Hard keywords, https://kotlinlang.org/docs/keyword-reference.html
+static KOTLIN: &[&str] = &[
+    "as",
+    "break",
+    "class",
+    "continue",
+    "do",
+    "else",
+    "false",
+    "for",
+    "fun",
+    "if",
+    "in",
+    "interface",
+    "is",
+    "null",
+    "object",
+    "package",
+    "return",
+    "super",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typealias",
+    "typeof",
+    "val",
+    "var",
+    "when",
+    "while",
+];
+
+static SWIFT: &[&str] = &[
+    "associatedtype",
+    "class",
+    "deinit",
+    "enum",
+    "extension",
+    "fileprivate",
+    "func",
+    "import",
+    "init",
+    "inout",
+    "internal",
+    "let",
+    "open",
+    "operator",
+    "private",
+    "precedencegroup",
+    "protocol",
+    "public",
+    "rethrows",
+    "static",
+    "struct",
+    "subscript",
+    "typealias",
+    "var",
+    "break",
+    "case",
+    "catch",
+    "continue",
+    "default",
+    "defer",
+    "do",
+    "else",
+    "fallthrough",
+    "for",
+    "guard",
+    "if",
+    "in",
+    "repeat",
+    "return",
+    "throw",
+    "switch",
+    "where",
+    "while",
+    "Any",
+    "as",
+    "await",
+    "false",
+    "is",
+    "nil",
+    "self",
+    "Self",
+    "super",
+    "throws",
+    "true",
+    "try",
+    "_",
+];
+
+static CSHARP: &[&str] = &[
+    "abstract",
+    "as",
+    "base",
+    "bool",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "checked",
+    "class",
+    "const",
+    "continue",
+    "decimal",
+    "default",
+    "delegate",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "event",
+    "explicit",
+    "extern",
+    "false",
+    "finally",
+    "fixed",
+    "float",
+    "for",
+    "foreach",
+    "goto",
+    "if",
+    "implicit",
+    "in",
+    "int",
+    "interface",
+    "internal",
+    "is",
+    "lock",
+    "long",
+    "namespace",
+    "new",
+    "null",
+    "object",
+    "operator",
+    "out",
+    "override",
+    "params",
+    "private",
+    "protected",
+    "public",
+    "readonly",
+    "ref",
+    "return",
+    "sbyte",
+    "sealed",
+    "short",
+    "sizeof",
+    "stackalloc",
+    "static",
+    "string",
+    "struct",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "uint",
+    "ulong",
+    "unchecked",
+    "unsafe",
+    "ushort",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "while",
+];
+
+static RUBY: &[&str] = &[
+    "__ENCODING__",
+    "__LINE__",
+    "__FILE__",
+    "BEGIN",
+    "END",
+    "alias",
+    "and",
+    "begin",
+    "break",
+    "case",
+    "class",
+    "def",
+    "defined?",
+    "do",
+    "else",
+    "elsif",
+    "end",
+    "ensure",
+    "false",
+    "for",
+    "if",
+    "in",
+    "module",
+    "next",
+    "nil",
+    "not",
+    "or",
+    "redo",
+    "rescue",
+    "retry",
+    "return",
+    "self",
+    "super",
+    "then",
+    "true",
+    "undef",
+    "unless",
+    "until",
+    "when",
+    "while",
+    "yield",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_keyword, escape_keyword_by_name, EscapeStyle, Language};
+
+    #[test]
+    fn test_escape_keyword() {
+        assert_eq!(
+            escape_keyword("type", Language::Rust2021, EscapeStyle::Suffix),
+            "type_"
+        );
+        assert_eq!(
+            escape_keyword("type", Language::Rust2021, EscapeStyle::Raw),
+            "r#type"
+        );
+        assert_eq!(
+            escape_keyword("self", Language::Rust2021, EscapeStyle::Raw),
+            "self_"
+        );
+        assert_eq!(
+            escape_keyword("async", Language::Rust2015, EscapeStyle::Raw),
+            "async"
+        );
+        assert_eq!(
+            escape_keyword("class", Language::CSharp, EscapeStyle::Raw),
+            "@class"
+        );
+        assert_eq!(
+            escape_keyword("in", Language::Kotlin, EscapeStyle::Raw),
+            "`in`"
+        );
+        assert_eq!(
+            escape_keyword("def", Language::Python, EscapeStyle::Prefix),
+            "_def"
+        );
+        assert_eq!(
+            escape_keyword("delete", Language::TypeScript, EscapeStyle::Suffix),
+            "delete_"
+        );
+        assert_eq!(
+            escape_keyword("foo", Language::Cpp, EscapeStyle::Suffix),
+            "foo"
+        );
+    }
+
+    #[test]
+    fn test_escape_keyword_by_name() {
+        assert_eq!(
+            escape_keyword_by_name("match", "rust", Some("raw")),
+            Ok(String::from("r#match"))
+        );
+        assert_eq!(
+            escape_keyword_by_name("func", "go", None),
+            Ok(String::from("func_"))
+        );
+        assert!(escape_keyword_by_name("func", "go", Some("raw")).is_err());
+        assert!(escape_keyword_by_name("func", "cobol", None).is_err());
+        assert!(escape_keyword_by_name("func", "go", Some("upcase")).is_err());
+    }
+
+    #[test]
+    fn test_language_from_name() {
+        assert_eq!(Language::from_name("rust"), Some(Language::Rust2021));
+        assert_eq!(Language::from_name("c++"), Some(Language::Cpp));
+        assert_eq!(Language::from_name("cobol"), None);
+        assert!(Language::from_name("swift")
+            .unwrap()
+            .supports_raw_identifiers());
+        assert!(!Language::from_name("go")
+            .unwrap()
+            .supports_raw_identifiers());
+    }
+}
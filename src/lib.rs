@@ -3,17 +3,34 @@
 extern crate liquid;
 extern crate serde;
 
+pub mod blocks;
+pub mod build_support;
+pub mod defs;
+mod diagnostics;
+pub mod doc_comments;
 pub mod filters;
+pub mod generators;
+mod globals;
 pub mod helpers;
+pub mod keywords;
 mod messages;
 #[allow(non_upper_case_globals)]
 mod messages_data;
+pub mod metadata;
+pub mod naming;
 mod nodes;
 #[allow(non_upper_case_globals)]
 mod nodes_data;
+pub mod overlay;
+mod queries;
+mod strict;
+pub mod string_literals;
+pub mod type_maps;
 
+pub use defs::{Annotations, Defs, MessageDef, MessageFieldDef, NodeDef, NodeFieldDef};
 pub use messages::*;
 pub use nodes::*;
+pub use overlay::Overlay;
 
 pub fn nodes() -> &'static [&'static Node] {
     nodes_data::ALL_NODES
@@ -23,13 +40,30 @@ pub fn messages() -> &'static [&'static Message] {
     messages_data::ALL_MESSAGES
 }
 
+mod template;
+pub use template::Template;
+
 mod liquid_template;
 pub use liquid_template::LiquidTemplate;
 
+#[cfg(feature = "minijinja")]
+mod minijinja_template;
+#[cfg(feature = "minijinja")]
+pub use minijinja_template::MiniJinjaTemplate;
+
+#[cfg(feature = "tera")]
+mod tera_template;
+#[cfg(feature = "tera")]
+pub use tera_template::TeraTemplate;
+
 pub mod reexports {
     pub mod liquid {
         pub use liquid_core::value;
     }
+    #[cfg(feature = "minijinja")]
+    pub use ::minijinja;
+    #[cfg(feature = "tera")]
+    pub use ::tera;
     pub mod serde {
         pub use serde::{ser::SerializeStruct, Serialize, Serializer};
     }
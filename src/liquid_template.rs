@@ -1,9 +1,18 @@
-use crate::messages_data;
-use crate::nodes_data;
+use crate::defs::Defs;
+use crate::naming::Acronyms;
+use crate::template::Partial;
+use crate::type_maps::TypeMap;
+use std::collections::BTreeMap;
 
 pub struct LiquidTemplate {
     path: String,
     src: String,
+    from_file: bool,
+    partials: Vec<Partial>,
+    acronyms: Acronyms,
+    type_maps: BTreeMap<String, TypeMap>,
+    strict: bool,
+    normalize_whitespace: bool,
     filters: Vec<Box<dyn liquid_core::parser::ParseFilter>>,
     globals: liquid::Object,
 }
@@ -18,26 +27,36 @@ impl LiquidTemplate {
         Self {
             path: path.clone(),
             src,
+            from_file: true,
+            partials: vec![],
+            acronyms: Acronyms::default(),
+            type_maps: TypeMap::builtins(),
+            strict: false,
+            normalize_whitespace: false,
             filters: crate::filters::all(),
             globals: liquid::object!({
-                "nodes": nodes_data::ALL_NODES,
-                "messages": messages_data::ALL_MESSAGES,
                 "template": path
             }),
         }
+        .with_defs(&Defs::builtin())
     }
 
     pub fn new_eval<S: AsRef<str>>(src: S) -> Self {
         Self {
             path: String::from("eval.liquid"),
             src: src.as_ref().to_string(),
+            from_file: false,
+            partials: vec![],
+            acronyms: Acronyms::default(),
+            type_maps: TypeMap::builtins(),
+            strict: false,
+            normalize_whitespace: false,
             filters: crate::filters::all(),
             globals: liquid::object!({
-                "nodes": nodes_data::ALL_NODES,
-                "messages": messages_data::ALL_MESSAGES,
                 "template": "eval.liquid"
             }),
         }
+        .with_defs(&Defs::builtin())
     }
 
     pub fn with_filter<F>(mut self, f: F) -> Self
@@ -48,22 +67,127 @@ impl LiquidTemplate {
         self
     }
 
+    pub fn with_partial<N: AsRef<str>, P: AsRef<str>>(mut self, name: N, path: P) -> Self {
+        self.partials.push(Partial::read(name, path));
+        self
+    }
+
+    /// Enables strict mode: rendering fails on unknown variables and object properties
+    /// in places where Liquid treats them as `nil` (`if`/`unless` conditions, `map`/`where` filters).
+    ///
+    /// Every variable of an `if`/`unless` condition must be defined, `or`/`and` don't short-circuit the check.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Post-processes rendered output with [`helpers::normalize_whitespace`](crate::helpers::normalize_whitespace):
+    /// strips trailing whitespace and collapses runs of blank lines left by Liquid loops.
+    pub fn normalize_whitespace(mut self) -> Self {
+        self.normalize_whitespace = true;
+        self
+    }
+
+    /// Registers words that case conversion filters (`to_snakecase` and others)
+    /// must keep as a single word, see [`naming::Acronyms`](crate::naming::Acronyms).
+    pub fn with_acronyms<S: AsRef<str>>(mut self, words: &[S]) -> Self {
+        for word in words {
+            self.acronyms.push(word);
+        }
+        self
+    }
+
+    /// Registers a type map for `map_type` filter, replaces a built-in one with the same name.
+    pub fn with_type_map(mut self, name: &str, type_map: TypeMap) -> Self {
+        self.type_maps.insert(name.to_owned(), type_map);
+        self
+    }
+
     pub fn with_global(mut self, name: &str, value: liquid_core::Value) -> Self {
         self.globals.insert(name.to_owned().into(), value);
         self
     }
 
+    /// Replaces `nodes` and `messages` globals with a given set (by default it's [`Defs::builtin`]).
+    pub fn with_defs(self, defs: &Defs) -> Self {
+        self.with_serde_global("nodes", &crate::globals::nodes(&defs.nodes))
+            .with_serde_global("messages", &crate::globals::messages(&defs.messages))
+    }
+
+    /// Same as [`with_global`](Self::with_global), but takes any `Serialize` value.
+    pub fn with_serde_global<T: serde::Serialize>(self, name: &str, value: &T) -> Self {
+        let value = liquid_core::to_value(value).unwrap_or_else(|e| {
+            eprintln!("Failed to convert global {} to Liquid value:\n{}", name, e);
+            std::process::exit(1);
+        });
+        self.with_global(name, value)
+    }
+
+    /// See [`Template::render`](crate::Template::render).
     pub fn render(self) -> String {
+        crate::Template::render(self)
+    }
+
+    /// See [`Template::dependencies`](crate::Template::dependencies).
+    pub fn dependencies(&self) -> Vec<&str> {
+        crate::Template::dependencies(self)
+    }
+
+    /// See [`Template::check`](crate::Template::check).
+    pub fn check<P: AsRef<str>>(self, path: P) -> bool {
+        crate::Template::check(self, path)
+    }
+}
+
+impl crate::Template for LiquidTemplate {
+    fn source_path(&self) -> Option<&str> {
+        if self.from_file {
+            Some(&self.path)
+        } else {
+            None
+        }
+    }
+
+    fn partial_paths(&self) -> Vec<&str> {
+        self.partials.iter().map(|p| p.path.as_str()).collect()
+    }
+
+    fn render_in_memory(self) -> String {
         let Self {
             path,
             src,
             filters,
             globals,
+            partials,
+            acronyms,
+            type_maps,
+            strict,
+            normalize_whitespace,
+            ..
         } = self;
 
-        println!("cargo:rerun-if-changed={}", path);
+        let mut source = liquid::partials::InMemorySource::new();
+        for partial in partials.iter() {
+            source.add(partial.name.as_str(), partial.src.as_str());
+        }
 
-        let mut builder = liquid::ParserBuilder::with_stdlib();
+        let mut builder = liquid::ParserBuilder::with_stdlib()
+            .partials(liquid::partials::EagerCompiler::new(source));
+        for filter in crate::filters::ConvertCase::all(&acronyms) {
+            builder = builder.filter(filter);
+        }
+        builder = builder.filter(crate::filters::MapType::new(type_maps));
+        for block in crate::blocks::all() {
+            builder = builder.block(block);
+        }
+        if strict {
+            for block in crate::strict::blocks() {
+                builder = builder.block(block);
+            }
+            for filter in crate::strict::filters() {
+                builder = builder.filter(filter);
+            }
+        }
         for filter in filters {
             builder = builder.filter(filter);
         }
@@ -74,13 +198,178 @@ impl LiquidTemplate {
         });
 
         let template = parser.parse(&src).unwrap_or_else(|e| {
-            eprintln!("Liquid template error ({}):\n{}", path, e);
+            eprintln!("Liquid template error ({}):", path);
+            eprintln!(
+                "{}",
+                crate::diagnostics::format_parse_error(&path, &src, &e)
+            );
             std::process::exit(1);
         });
 
-        template.render(&globals).unwrap_or_else(|e| {
-            eprintln!("Failed to render {} template:\n{}", path, e);
+        let output = template.render(&globals).unwrap_or_else(|e| {
+            eprintln!("Failed to render {} template:", path);
+            eprintln!(
+                "{}",
+                crate::diagnostics::format_render_error(&path, &src, &partials, &globals, &e)
+            );
             std::process::exit(1);
-        })
+        });
+
+        if normalize_whitespace {
+            crate::helpers::normalize_whitespace(&output)
+        } else {
+            output
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LiquidTemplate;
+
+    #[test]
+    fn test_check() {
+        let path = std::env::temp_dir().join(format!(
+            "lib-ruby-parser-nodes-test-check-{}.txt",
+            std::process::id()
+        ));
+        let path = path.to_str().unwrap();
+
+        std::fs::write(path, "nodes: 124\n").unwrap();
+        assert!(LiquidTemplate::new_eval("nodes: {{ nodes.size }}\n").check(path));
+
+        std::fs::write(path, "nodes: 123\n").unwrap();
+        assert!(!LiquidTemplate::new_eval("nodes: {{ nodes.size }}\n").check(path));
+
+        std::fs::remove_file(path).unwrap();
+        assert!(!LiquidTemplate::new_eval("nodes: {{ nodes.size }}\n").check(path));
+    }
+
+    #[test]
+    fn test_acronyms() {
+        let template = LiquidTemplate::new_eval(
+            "{{ 'CSend' | to_snakecase }} {{ 'CSend' | to_screaming_snakecase }} {{ 'csend' | to_pascalcase }}",
+        );
+        assert_eq!(template.render(), "csend CSEND CSend");
+
+        let template = LiquidTemplate::new_eval(
+            "{{ 'HTTPServer' | to_snakecase }} {{ 'http_server' | to_pascalcase }} {{ 'Self_' | to_snakecase }}",
+        )
+        .with_acronyms(&["HTTP"]);
+        assert_eq!(template.render(), "http_server HTTPServer self_");
+    }
+
+    #[test]
+    fn test_render_doc_comment() {
+        let template = LiquidTemplate::new_eval(
+            "{% for node in nodes %}{% if node.camelcase_name == 'Alias' %}{{ node.fields[2].comment | render_doc_comment: \"doxygen\", 4 }}{% endif %}{% endfor %}",
+        );
+        assert_eq!(
+            template.render(),
+            [
+                "/**",
+                "     * Location of the `alias` keyword",
+                "     *",
+                "     * @verbatim",
+                "     * alias foo bar",
+                "     * ~~~~~",
+                "     * @endverbatim",
+                "     */",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_string_literal() {
+        let template = LiquidTemplate::new_eval(
+            "{% for node in nodes %}{% if node.camelcase_name == 'Alias' %}{{ node.fields[0].comment[2] | string_literal: \"c\" }}{% endif %}{% endfor %}",
+        );
+        assert_eq!(
+            template.render(),
+            r#""`Sym(\"foo\")` node for `alias :foo :bar`""#
+        );
+    }
+
+    #[test]
+    fn test_normalize_whitespace() {
+        let src = "{% for node in nodes limit: 2 %}\n\n{{ node.camelcase_name }}  \n\n{% endfor %}";
+        assert_eq!(
+            LiquidTemplate::new_eval(src)
+                .normalize_whitespace()
+                .render(),
+            "Alias\n\nAnd\n"
+        );
+    }
+
+    #[test]
+    fn test_serde_global() {
+        #[derive(serde::Serialize)]
+        struct Config {
+            prefix: &'static str,
+            types: std::collections::BTreeMap<&'static str, &'static str>,
+        }
+
+        let config = Config {
+            prefix: "LIB_RUBY_PARSER_",
+            types: [("Loc", "Loc"), ("Node", "Node*")].into_iter().collect(),
+        };
+        let template = LiquidTemplate::new_eval("{{ config.prefix }}{{ config.types.Node }}")
+            .with_serde_global("config", &config);
+        assert_eq!(template.render(), "LIB_RUBY_PARSER_Node*");
+    }
+
+    #[test]
+    fn test_with_defs() {
+        let mut defs = crate::Defs::builtin();
+        defs.nodes
+            .retain(|node| node.camelcase_name.starts_with('A'));
+        defs.nodes[0].camelcase_name = String::from("Alias2");
+
+        let template = LiquidTemplate::new_eval(
+            "{{ nodes.size }} {{ nodes[0].camelcase_name }} {{ nodes[0].fields[0].node.upper_name }} {{ messages.size }}",
+        )
+        .with_defs(&defs);
+        assert_eq!(template.render(), "8 Alias2 ALIAS2 90");
+    }
+
+    #[test]
+    fn test_map_type() {
+        let template = LiquidTemplate::new_eval(
+            "{{ 'MaybeNode' | map_type: 'rust' }} {{ 'MaybeNode' | map_type: 'c' }} {{ 'Byte' | map_type: 'ts' }}",
+        );
+        assert_eq!(template.render(), "Option<Box<Node>> Node* number");
+
+        let template = LiquidTemplate::new_eval(
+            "{{ 'Node' | map_type: 'rust' }} {{ 'Nodes' | map_type: 'zig' }}",
+        )
+        .with_type_map(
+            "rust",
+            crate::type_maps::TypeMap::builtin("rust")
+                .unwrap()
+                .with("Node", "NodeRef"),
+        )
+        .with_type_map(
+            "zig",
+            crate::type_maps::TypeMap::new(&[("Nodes", "[]Node")]),
+        );
+        assert_eq!(template.render(), "NodeRef []Node");
+    }
+
+    #[test]
+    fn test_partials() {
+        let path = std::env::temp_dir().join(format!(
+            "lib-ruby-parser-nodes-test-partial-{}.liquid",
+            std::process::id()
+        ));
+        let path = path.to_str().unwrap();
+        std::fs::write(path, "messages: {{ messages.size }}").unwrap();
+
+        let template = LiquidTemplate::new_eval("{% include 'messages_count' %}")
+            .with_partial("messages_count", path);
+        assert_eq!(template.dependencies(), vec![path]);
+        assert_eq!(template.render(), "messages: 90");
+
+        std::fs::remove_file(path).unwrap();
     }
 }
@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub struct Message {
@@ -7,6 +7,16 @@ pub struct Message {
     pub comment: &'static [&'static str],
 }
 
+impl Message {
+    pub fn upper_name(&self) -> String {
+        crate::helpers::camelcase_to_snakecase(self.camelcase_name).to_uppercase()
+    }
+
+    pub fn lower_name(&self) -> String {
+        crate::helpers::camelcase_to_snakecase(self.camelcase_name).to_lowercase()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub struct MessageField {
     pub snakecase_name: &'static str,
@@ -14,7 +24,7 @@ pub struct MessageField {
     pub comment: &'static [&'static str],
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum MessageFieldType {
     Str,
     Byte,
@@ -0,0 +1,130 @@
+//! Export of [`nodes()`](crate::nodes) and [`messages()`](crate::messages) as a JSON document
+//! for tools that can't link Rust, described by [`JSON_SCHEMA`].
+
+use crate::defs::{Defs, MessageDef, NodeDef};
+use crate::{Message, Node};
+use serde::Serialize;
+
+/// Version of the document format, incremented on breaking changes.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// JSON Schema of the document returned by [`to_json`].
+pub const JSON_SCHEMA: &str = include_str!("../schema/metadata.schema.json");
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Metadata {
+    pub format_version: u32,
+    pub crate_version: &'static str,
+    pub nodes: &'static [&'static Node],
+    pub messages: &'static [&'static Message],
+}
+
+pub fn metadata() -> Metadata {
+    Metadata {
+        format_version: FORMAT_VERSION,
+        crate_version: env!("CARGO_PKG_VERSION"),
+        nodes: crate::nodes(),
+        messages: crate::messages(),
+    }
+}
+
+/// Pretty-printed JSON with all nodes and messages (including comments and field types).
+pub fn to_json() -> String {
+    serde_json::to_string_pretty(&metadata()).expect("metadata is always serializable")
+}
+
+#[derive(Serialize)]
+struct DefsMetadata<'a> {
+    format_version: u32,
+    crate_version: &'static str,
+    nodes: &'a [NodeDef],
+    messages: &'a [MessageDef],
+}
+
+/// Same as [`to_json`], but for a custom set of nodes and messages, non-empty annotations are included.
+pub fn to_json_with(defs: &Defs) -> String {
+    let metadata = DefsMetadata {
+        format_version: FORMAT_VERSION,
+        crate_version: env!("CARGO_PKG_VERSION"),
+        nodes: &defs.nodes,
+        messages: &defs.messages,
+    };
+    serde_json::to_string_pretty(&metadata).expect("metadata is always serializable")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    #[test]
+    fn test_to_json() {
+        let json: Value = serde_json::from_str(&super::to_json()).unwrap();
+        assert_eq!(json["format_version"], 1);
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 124);
+        assert_eq!(json["messages"].as_array().unwrap().len(), 90);
+
+        let alias = &json["nodes"][0];
+        assert_eq!(alias["camelcase_name"], "Alias");
+        assert_eq!(alias["fields"][0]["field_type"], "Node");
+        assert_eq!(alias["comment"][0], "Represents `alias to from` statement.");
+    }
+
+    #[test]
+    fn test_to_json_with() {
+        let defs = crate::Defs::builtin();
+        assert_eq!(super::to_json_with(&defs), super::to_json());
+
+        let overlay = crate::Overlay::from_json(
+            r#"{ "nodes": { "Send.method_name": { "ffi_name": "method" } } }"#,
+        )
+        .unwrap();
+        let defs = defs.with_overlay(&overlay).unwrap();
+        let json: Value = serde_json::from_str(&super::to_json_with(&defs)).unwrap();
+        let send = json["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|node| node["camelcase_name"] == "Send")
+            .unwrap();
+        assert_eq!(send["fields"][1]["annotations"]["ffi_name"], "method");
+        assert!(send.get("annotations").is_none());
+        assert_eq!(crate::Defs::from_json(&json.to_string()).unwrap(), defs);
+    }
+
+    #[test]
+    fn test_schema_matches_metadata() {
+        let schema: Value = serde_json::from_str(super::JSON_SCHEMA).unwrap();
+        assert_eq!(schema["properties"]["format_version"]["const"], 1);
+
+        let enum_of = |name: &str| -> Vec<String> {
+            schema["$defs"][name]["enum"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|v| v.as_str().unwrap().to_string())
+                .collect()
+        };
+        let node_field_types = enum_of("node_field_type");
+        let message_field_types = enum_of("message_field_type");
+
+        let json: Value = serde_json::from_str(&super::to_json()).unwrap();
+        for node in json["nodes"].as_array().unwrap() {
+            for field in node["fields"].as_array().unwrap() {
+                let field_type = field["field_type"].as_str().unwrap().to_string();
+                assert!(node_field_types.contains(&field_type), "{}", field_type);
+
+                let keys = field.as_object().unwrap().keys().collect::<Vec<_>>();
+                let properties = schema["$defs"]["node_field"]["properties"]
+                    .as_object()
+                    .unwrap();
+                assert!(keys.iter().all(|key| properties.contains_key(*key)));
+            }
+        }
+        for message in json["messages"].as_array().unwrap() {
+            for field in message["fields"].as_array().unwrap() {
+                let field_type = field["field_type"].as_str().unwrap().to_string();
+                assert!(message_field_types.contains(&field_type), "{}", field_type);
+            }
+        }
+    }
+}
@@ -0,0 +1,340 @@
+use crate::defs::Defs;
+use crate::helpers;
+use crate::naming::{Acronyms, Case};
+use crate::template::Partial;
+use crate::type_maps::TypeMap;
+use minijinja::{AutoEscape, Environment, UndefinedBehavior, Value};
+use std::collections::BTreeMap;
+
+pub struct MiniJinjaTemplate {
+    path: String,
+    src: String,
+    from_file: bool,
+    partials: Vec<Partial>,
+    acronyms: Acronyms,
+    type_maps: BTreeMap<String, TypeMap>,
+    strict: bool,
+    globals: BTreeMap<String, Value>,
+}
+
+fn default_globals(template: &str) -> BTreeMap<String, Value> {
+    let defs = Defs::builtin();
+    let mut globals = BTreeMap::new();
+    globals.insert(
+        String::from("nodes"),
+        Value::from_serialize(crate::globals::nodes(&defs.nodes)),
+    );
+    globals.insert(
+        String::from("messages"),
+        Value::from_serialize(crate::globals::messages(&defs.messages)),
+    );
+    globals.insert(String::from("template"), Value::from(template));
+    globals
+}
+
+impl MiniJinjaTemplate {
+    pub fn new<P: AsRef<str>>(path: P) -> Self {
+        let path = path.as_ref().to_string();
+        let src = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+            eprintln!("Failed to read {}:\n{}", path, e);
+            std::process::exit(1);
+        });
+        Self {
+            globals: default_globals(&path),
+            path,
+            src,
+            from_file: true,
+            partials: vec![],
+            acronyms: Acronyms::default(),
+            type_maps: TypeMap::builtins(),
+            strict: false,
+        }
+    }
+
+    pub fn new_eval<S: AsRef<str>>(src: S) -> Self {
+        Self {
+            path: String::from("eval.jinja"),
+            src: src.as_ref().to_string(),
+            from_file: false,
+            partials: vec![],
+            acronyms: Acronyms::default(),
+            type_maps: TypeMap::builtins(),
+            strict: false,
+            globals: default_globals("eval.jinja"),
+        }
+    }
+
+    pub fn with_partial<N: AsRef<str>, P: AsRef<str>>(mut self, name: N, path: P) -> Self {
+        self.partials.push(Partial::read(name, path));
+        self
+    }
+
+    /// Enables strict mode: rendering fails on any undefined variable or attribute.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Registers words that case conversion filters (`to_snakecase` and others)
+    /// must keep as a single word, see [`naming::Acronyms`](crate::naming::Acronyms).
+    pub fn with_acronyms<S: AsRef<str>>(mut self, words: &[S]) -> Self {
+        for word in words {
+            self.acronyms.push(word);
+        }
+        self
+    }
+
+    /// Registers a type map for `map_type` filter, replaces a built-in one with the same name.
+    pub fn with_type_map(mut self, name: &str, type_map: TypeMap) -> Self {
+        self.type_maps.insert(name.to_owned(), type_map);
+        self
+    }
+
+    pub fn with_global(mut self, name: &str, value: Value) -> Self {
+        self.globals.insert(name.to_owned(), value);
+        self
+    }
+
+    /// Replaces `nodes` and `messages` globals with a given set (by default it's [`Defs::builtin`]).
+    pub fn with_defs(self, defs: &Defs) -> Self {
+        self.with_serde_global("nodes", &crate::globals::nodes(&defs.nodes))
+            .with_serde_global("messages", &crate::globals::messages(&defs.messages))
+    }
+
+    /// Same as [`with_global`](Self::with_global), but takes any `Serialize` value.
+    pub fn with_serde_global<T: serde::Serialize>(self, name: &str, value: &T) -> Self {
+        self.with_global(name, Value::from_serialize(value))
+    }
+
+    /// See [`Template::render`](crate::Template::render).
+    pub fn render(self) -> String {
+        crate::Template::render(self)
+    }
+
+    /// See [`Template::dependencies`](crate::Template::dependencies).
+    pub fn dependencies(&self) -> Vec<&str> {
+        crate::Template::dependencies(self)
+    }
+
+    /// See [`Template::check`](crate::Template::check).
+    pub fn check<P: AsRef<str>>(self, path: P) -> bool {
+        crate::Template::check(self, path)
+    }
+}
+
+impl crate::Template for MiniJinjaTemplate {
+    fn source_path(&self) -> Option<&str> {
+        if self.from_file {
+            Some(&self.path)
+        } else {
+            None
+        }
+    }
+
+    fn partial_paths(&self) -> Vec<&str> {
+        self.partials.iter().map(|p| p.path.as_str()).collect()
+    }
+
+    fn render_in_memory(self) -> String {
+        let Self {
+            path,
+            src,
+            partials,
+            acronyms,
+            type_maps,
+            strict,
+            globals,
+            ..
+        } = self;
+
+        let mut env = Environment::new();
+        env.set_auto_escape_callback(|_| AutoEscape::None);
+        if strict {
+            env.set_undefined_behavior(UndefinedBehavior::Strict);
+        }
+        register_filters(&mut env, &acronyms, type_maps);
+
+        for partial in partials.iter() {
+            env.add_template(&partial.name, &partial.src)
+                .unwrap_or_else(|e| {
+                    eprintln!("MiniJinja template error ({}):\n{:#}", partial.path, e);
+                    std::process::exit(1);
+                });
+        }
+
+        env.add_template(&path, &src).unwrap_or_else(|e| {
+            eprintln!("MiniJinja template error ({}):\n{:#}", path, e);
+            std::process::exit(1);
+        });
+
+        env.get_template(&path)
+            .and_then(|template| template.render(&globals))
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to render {} template:\n{:#}", path, e);
+                std::process::exit(1);
+            })
+    }
+}
+
+impl crate::queries::Item for Value {
+    fn str_attr(&self, name: &str) -> Option<String> {
+        let value = self.get_attr(name).ok()?;
+        value.as_str().map(String::from)
+    }
+
+    fn list_attr(&self, name: &str) -> Option<Vec<Self>> {
+        let value = self.get_attr(name).ok()?;
+        if value.is_undefined() || value.is_none() {
+            return None;
+        }
+        Some(value.try_iter().ok()?.collect())
+    }
+}
+
+fn register_query_filters(env: &mut Environment) {
+    env.add_filter("with_field_type", |items: Vec<Value>, field_type: &str| {
+        Value::from(crate::queries::with_field_type(items, field_type))
+    });
+    env.add_filter("having_field", |items: Vec<Value>, name: &str| {
+        Value::from(crate::queries::having_field(items, name))
+    });
+    env.add_filter("node_fields", |fields: Vec<Value>| {
+        Value::from(crate::queries::node_fields(fields))
+    });
+    env.add_filter("loc_fields", |fields: Vec<Value>| {
+        Value::from(crate::queries::loc_fields(fields))
+    });
+    env.add_filter("distinct_types", |items: Vec<Value>| {
+        crate::queries::distinct_types(items)
+    });
+}
+
+fn register_filters(
+    env: &mut Environment,
+    acronyms: &Acronyms,
+    type_maps: BTreeMap<String, TypeMap>,
+) {
+    register_query_filters(env);
+    env.add_filter("map_type", move |field_type: &str, name: &str| {
+        let type_map = type_maps.get(name).ok_or_else(|| {
+            minijinja::Error::new(
+                minijinja::ErrorKind::InvalidOperation,
+                format!("unknown type map {}", name),
+            )
+        })?;
+        type_map.get(field_type).map(str::to_string).ok_or_else(|| {
+            minijinja::Error::new(
+                minijinja::ErrorKind::InvalidOperation,
+                format!("type map {} has no {}", name, field_type),
+            )
+        })
+    });
+    for case in Case::all() {
+        let acronyms = acronyms.clone();
+        env.add_filter(case.filter_name(), move |s: &str| {
+            crate::naming::convert(s, case, &acronyms)
+        });
+    }
+    env.add_filter("camelcase_to_snakecase", |s: &str| {
+        helpers::camelcase_to_snakecase(s)
+    });
+    env.add_filter("snakecase_to_camelcase", |s: &str| {
+        helpers::snakecase_to_camelcase(s)
+    });
+    env.add_filter("escape_rust_keyword", |s: &str| {
+        helpers::escape_rust_keyword(s)
+    });
+    env.add_filter("escape_c_keyword", |s: &str| helpers::escape_c_keyword(s));
+    env.add_filter("escape_cpp_keyword", |s: &str| {
+        helpers::escape_cpp_keyword(s)
+    });
+    env.add_filter("escape_js_keyword", |s: &str| helpers::escape_js_keyword(s));
+    env.add_filter(
+        "escape_keyword",
+        |s: &str, language: &str, style: Option<&str>| {
+            crate::keywords::escape_keyword_by_name(s, language, style)
+                .map_err(|e| minijinja::Error::new(minijinja::ErrorKind::InvalidOperation, e))
+        },
+    );
+    env.add_filter("string_literal", |s: &str, language: &str| {
+        crate::string_literals::string_literal_by_name(s, language)
+            .map_err(|e| minijinja::Error::new(minijinja::ErrorKind::InvalidOperation, e))
+    });
+    env.add_filter(
+        "render_comment",
+        |lines: Vec<String>, prefix: &str, offset: usize| {
+            helpers::render_comment(&lines, prefix, offset)
+        },
+    );
+    env.add_filter(
+        "render_doc_comment",
+        |lines: Vec<String>, style: &str, offset: Option<usize>| {
+            crate::doc_comments::render_doc_comment_by_name(&lines, style, offset.unwrap_or(0))
+                .map_err(|e| minijinja::Error::new(minijinja::ErrorKind::InvalidOperation, e))
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MiniJinjaTemplate;
+
+    #[test]
+    fn test_render() {
+        let output = MiniJinjaTemplate::new_eval(
+            "{{ nodes | length }} {{ messages | length }} {{ 'FooBar' | camelcase_to_snakecase }}",
+        )
+        .render();
+        assert_eq!(output, "124 90 foo_bar");
+    }
+
+    #[test]
+    fn test_escape_keyword() {
+        let output =
+            MiniJinjaTemplate::new_eval("{{ 'type' | escape_keyword('rust', 'raw') }}").render();
+        assert_eq!(output, "r#type");
+    }
+
+    #[test]
+    fn test_map_type() {
+        let output = MiniJinjaTemplate::new_eval("{{ 'MaybeLoc' | map_type('cpp') }}").render();
+        assert_eq!(output, "std::optional<Loc>");
+    }
+
+    #[test]
+    fn test_render_doc_comment() {
+        let output = MiniJinjaTemplate::new_eval(
+            "{% for node in nodes if node.camelcase_name == 'Alias' %}{{ node.comment | render_doc_comment('javadoc') }}{% endfor %}",
+        )
+        .render();
+        assert_eq!(
+            output,
+            "/**\n * Represents {@code alias to from} statement.\n */"
+        );
+    }
+
+    #[test]
+    fn test_string_literal() {
+        let output =
+            MiniJinjaTemplate::new_eval("{{ 'say \"hi\"' | string_literal('java') }}").render();
+        assert_eq!(output, r#""say \"hi\"""#);
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let output = MiniJinjaTemplate::new_eval(
+            "{{ nodes | with_field_type('RegexpOptions') | map(attribute='camelcase_name') | join(',') }} {{ nodes[0].fields | node_fields | length }} {{ messages | distinct_types | join(',') }}",
+        )
+        .render();
+        assert_eq!(output, "Regexp 2 Str,Byte");
+    }
+
+    #[test]
+    fn test_render_comment() {
+        let output = MiniJinjaTemplate::new_eval(
+            "{% for node in nodes if node.camelcase_name == 'Alias' %}{{ node.comment | render_comment('///', 0) }}{% endfor %}",
+        )
+        .render();
+        assert_eq!(output, "/// Represents `alias to from` statement.");
+    }
+}
@@ -0,0 +1,296 @@
+//! Conversion of identifiers between naming conventions.
+//!
+//! Unlike [`helpers::camelcase_to_snakecase`](crate::helpers::camelcase_to_snakecase)
+//! that starts a new word on every uppercase letter, identifiers are split into words
+//! on case boundaries (`HTTPServer` -> `HTTP`, `Server`) and separators (`_`, `-`, `.`, ` `),
+//! and words from [`Acronyms`] dictionary are never split (`CSend` -> `csend`).
+//!
+//! A trailing `_` is kept (`Self_` -> `self_`), it's used to escape keywords.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    /// `foo_bar`
+    Snake,
+    /// `FOO_BAR`
+    ScreamingSnake,
+    /// `fooBar`
+    Camel,
+    /// `FooBar`
+    Pascal,
+    /// `foo-bar`
+    Kebab,
+    /// `foo.bar`
+    Dotted,
+}
+
+impl Case {
+    pub fn all() -> [Case; 6] {
+        [
+            Self::Snake,
+            Self::ScreamingSnake,
+            Self::Camel,
+            Self::Pascal,
+            Self::Kebab,
+            Self::Dotted,
+        ]
+    }
+
+    /// Name of the template filter that converts to this case
+    pub fn filter_name(self) -> &'static str {
+        match self {
+            Self::Snake => "to_snakecase",
+            Self::ScreamingSnake => "to_screaming_snakecase",
+            Self::Camel => "to_camelcase",
+            Self::Pascal => "to_pascalcase",
+            Self::Kebab => "to_kebabcase",
+            Self::Dotted => "to_dottedcase",
+        }
+    }
+}
+
+/// Dictionary of words that must be kept as a single word and
+/// written with their own casing in `Pascal`/`Camel` cases (like `HTTP` or `CSend`).
+///
+/// Words are matched case-insensitively, so `csend`, `CSend` and `CSEND` all match `CSend`.
+///
+/// [`Acronyms::default`] contains built-in nodes with a one-letter prefix
+/// (`CSend`, `EFlipFlop`, `SClass`, ...), use [`Acronyms::new`] to start from an empty dictionary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Acronyms {
+    words: Vec<String>,
+}
+
+impl Acronyms {
+    pub fn new<S: AsRef<str>>(words: &[S]) -> Self {
+        let mut words = words
+            .iter()
+            .map(|w| w.as_ref().to_string())
+            .collect::<Vec<_>>();
+        // longest match wins
+        words.sort_by_key(|w| std::cmp::Reverse(w.len()));
+        Self { words }
+    }
+
+    pub fn push<S: AsRef<str>>(&mut self, word: S) {
+        self.words.push(word.as_ref().to_string());
+        self.words.sort_by_key(|w| std::cmp::Reverse(w.len()));
+    }
+
+    fn match_at(&self, chars: &[char], start: usize) -> Option<&str> {
+        self.words.iter().map(String::as_str).find(|word| {
+            let word = word.chars().collect::<Vec<_>>();
+            let end = start + word.len();
+            if end > chars.len() {
+                return false;
+            }
+            let same = chars[start..end]
+                .iter()
+                .zip(word.iter())
+                .all(|(a, b)| a.to_lowercase().eq(b.to_lowercase()));
+            let at_boundary = match chars.get(end) {
+                None => true,
+                Some(c) => is_separator(*c) || c.is_uppercase() || c.is_ascii_digit(),
+            };
+            same && at_boundary
+        })
+    }
+}
+
+impl Default for Acronyms {
+    fn default() -> Self {
+        let words = crate::nodes()
+            .iter()
+            .map(|node| node.camelcase_name)
+            .filter(|name| name.chars().nth(1).is_some_and(char::is_uppercase))
+            .collect::<Vec<_>>();
+        Self::new(&words)
+    }
+}
+
+fn is_separator(c: char) -> bool {
+    matches!(c, '_' | '-' | '.' | ' ')
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Word {
+    text: String,
+    is_acronym: bool,
+}
+
+fn split_words(s: &str, acronyms: &Acronyms) -> Vec<Word> {
+    let chars = s.chars().collect::<Vec<_>>();
+    let mut words = vec![];
+    let mut current = String::new();
+
+    let mut idx = 0;
+    while idx < chars.len() {
+        let c = chars[idx];
+
+        if is_separator(c) {
+            flush(&mut words, &mut current);
+            idx += 1;
+            continue;
+        }
+
+        let prev = if idx == 0 { None } else { Some(chars[idx - 1]) };
+        let next = chars.get(idx + 1).copied();
+
+        let starts_word = match prev {
+            None => true,
+            Some(prev) if is_separator(prev) => true,
+            Some(prev) => {
+                c.is_uppercase()
+                    && (prev.is_lowercase()
+                        || prev.is_ascii_digit()
+                        || (prev.is_uppercase() && next.map(char::is_lowercase).unwrap_or(false)))
+            }
+        };
+
+        if starts_word {
+            flush(&mut words, &mut current);
+            if let Some(acronym) = acronyms.match_at(&chars, idx) {
+                words.push(Word {
+                    text: acronym.to_string(),
+                    is_acronym: true,
+                });
+                idx += acronym.chars().count();
+                continue;
+            }
+        }
+
+        current.push(c);
+        idx += 1;
+    }
+    flush(&mut words, &mut current);
+
+    words
+}
+
+fn flush(words: &mut Vec<Word>, current: &mut String) {
+    if !current.is_empty() {
+        words.push(Word {
+            text: std::mem::take(current),
+            is_acronym: false,
+        });
+    }
+}
+
+fn capitalize(word: &Word) -> String {
+    if word.is_acronym {
+        return word.text.clone();
+    }
+    let mut chars = word.text.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(first) => first.to_uppercase().collect::<String>() + &chars.as_str().to_lowercase(),
+    }
+}
+
+fn join_lowercase(words: &[Word], separator: &str) -> String {
+    words
+        .iter()
+        .map(|w| w.text.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+pub fn convert(s: &str, case: Case, acronyms: &Acronyms) -> String {
+    let words = split_words(s, acronyms);
+
+    let mut out = match case {
+        Case::Snake => join_lowercase(&words, "_"),
+        Case::ScreamingSnake => join_lowercase(&words, "_").to_uppercase(),
+        Case::Kebab => join_lowercase(&words, "-"),
+        Case::Dotted => join_lowercase(&words, "."),
+        Case::Pascal => words.iter().map(capitalize).collect(),
+        Case::Camel => words
+            .iter()
+            .enumerate()
+            .map(|(idx, w)| {
+                if idx == 0 {
+                    w.text.to_lowercase()
+                } else {
+                    capitalize(w)
+                }
+            })
+            .collect(),
+    };
+    if s.ends_with('_') {
+        out.push('_');
+    }
+    out
+}
+
+pub fn to_snakecase(s: &str) -> String {
+    convert(s, Case::Snake, &Acronyms::default())
+}
+
+pub fn to_screaming_snakecase(s: &str) -> String {
+    convert(s, Case::ScreamingSnake, &Acronyms::default())
+}
+
+pub fn to_camelcase(s: &str) -> String {
+    convert(s, Case::Camel, &Acronyms::default())
+}
+
+pub fn to_pascalcase(s: &str) -> String {
+    convert(s, Case::Pascal, &Acronyms::default())
+}
+
+pub fn to_kebabcase(s: &str) -> String {
+    convert(s, Case::Kebab, &Acronyms::default())
+}
+
+pub fn to_dottedcase(s: &str) -> String {
+    convert(s, Case::Dotted, &Acronyms::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cases() {
+        assert_eq!(to_snakecase("FooBar"), "foo_bar");
+        assert_eq!(to_screaming_snakecase("FooBar"), "FOO_BAR");
+        assert_eq!(to_camelcase("foo_bar"), "fooBar");
+        assert_eq!(to_pascalcase("foo_bar"), "FooBar");
+        assert_eq!(to_kebabcase("FooBar"), "foo-bar");
+        assert_eq!(to_dottedcase("foo_bar"), "foo.bar");
+    }
+
+    #[test]
+    fn test_word_boundaries() {
+        assert_eq!(to_snakecase("HTTPServer"), "http_server");
+        assert_eq!(to_snakecase("Self_"), "self_");
+        assert_eq!(to_pascalcase("self_"), "Self_");
+        assert_eq!(to_snakecase("U8"), "u8");
+        assert_eq!(to_snakecase("expression_l"), "expression_l");
+        assert_eq!(to_pascalcase("expression_l"), "ExpressionL");
+        assert_eq!(to_snakecase("CSend"), "csend");
+        assert_eq!(to_snakecase("EFlipFlop"), "eflipflop");
+        assert_eq!(to_pascalcase("zsuper"), "ZSuper");
+        assert_eq!(
+            convert("CSend", Case::Snake, &Acronyms::new::<&str>(&[])),
+            "c_send"
+        );
+    }
+
+    #[test]
+    fn test_acronyms() {
+        let acronyms = Acronyms::new(&["CSend", "EFlipFlop", "HTTP"]);
+
+        assert_eq!(convert("CSend", Case::Snake, &acronyms), "csend");
+        assert_eq!(convert("csend", Case::Pascal, &acronyms), "CSend");
+        assert_eq!(convert("CSend", Case::ScreamingSnake, &acronyms), "CSEND");
+        assert_eq!(convert("CSend", Case::Camel, &acronyms), "csend");
+        assert_eq!(convert("EFlipFlop", Case::Kebab, &acronyms), "eflipflop");
+        assert_eq!(
+            convert("http_server", Case::Pascal, &acronyms),
+            "HTTPServer"
+        );
+        assert_eq!(convert("HttpServer", Case::Camel, &acronyms), "httpServer");
+        // not on a word boundary
+        assert_eq!(convert("CSender", Case::Snake, &acronyms), "c_sender");
+    }
+}
@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub struct Node {
@@ -16,6 +16,10 @@ impl Node {
     pub fn lower_name(&self) -> String {
         crate::helpers::camelcase_to_snakecase(self.camelcase_name).to_lowercase()
     }
+
+    pub fn has_loc_fields(&self) -> bool {
+        self.fields.iter().any(|f| f.field_type.is_loc())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
@@ -26,7 +30,7 @@ pub struct NodeField {
     pub comment: &'static [&'static str],
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum NodeFieldType {
     Node,
     Nodes,
@@ -41,3 +45,26 @@ pub enum NodeFieldType {
     StringValue,
     U8,
 }
+
+impl NodeFieldType {
+    /// `Node`, `Nodes`, `MaybeNode` or `RegexpOptions`
+    pub fn is_node(&self) -> bool {
+        matches!(
+            self,
+            Self::Node | Self::Nodes | Self::MaybeNode | Self::RegexpOptions
+        )
+    }
+
+    /// `Loc` or `MaybeLoc`
+    pub fn is_loc(&self) -> bool {
+        matches!(self, Self::Loc | Self::MaybeLoc)
+    }
+
+    /// Field may be absent (`MaybeNode`, `RegexpOptions`, `MaybeLoc`, `MaybeStr` or `Chars`)
+    pub fn is_optional(&self) -> bool {
+        matches!(
+            self,
+            Self::MaybeNode | Self::RegexpOptions | Self::MaybeLoc | Self::MaybeStr | Self::Chars
+        )
+    }
+}
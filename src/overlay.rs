@@ -0,0 +1,192 @@
+//! Extra key/value annotations attached to nodes, messages and fields.
+//!
+//! Keys are either a node/message name (`Send`) or a name followed by a field name
+//! (`Send.method_name`):
+//!
+//! ```json
+//! {
+//!     "nodes": {
+//!         "Send": { "ruby_class": "Send" },
+//!         "Send.method_name": { "ffi_name": "method" }
+//!     },
+//!     "messages": {
+//!         "UnterminatedHeredoc": { "severity": "error" }
+//!     }
+//! }
+//! ```
+//!
+//! Annotations are available in templates as `node.annotations`, `field.annotations`, etc.
+//! Use `{% if node.annotations contains 'key' %}` to check if an annotation is set,
+//! unlike `{% if node.annotations.key %}` it also works in strict mode.
+
+use crate::defs::Annotations;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Overlay {
+    #[serde(default)]
+    pub nodes: BTreeMap<String, Annotations>,
+    #[serde(default)]
+    pub messages: BTreeMap<String, Annotations>,
+}
+
+// Shared by nodes and messages: name, own annotations, fields (name and annotations)
+trait Annotated {
+    fn name(&self) -> &str;
+    fn annotations_mut(&mut self) -> &mut Annotations;
+    fn field_annotations_mut(&mut self, field: &str) -> Option<&mut Annotations>;
+}
+
+impl Annotated for crate::NodeDef {
+    fn name(&self) -> &str {
+        &self.camelcase_name
+    }
+
+    fn annotations_mut(&mut self) -> &mut Annotations {
+        &mut self.annotations
+    }
+
+    fn field_annotations_mut(&mut self, field: &str) -> Option<&mut Annotations> {
+        self.fields
+            .iter_mut()
+            .find(|f| f.snakecase_name == field)
+            .map(|f| &mut f.annotations)
+    }
+}
+
+impl Annotated for crate::MessageDef {
+    fn name(&self) -> &str {
+        &self.camelcase_name
+    }
+
+    fn annotations_mut(&mut self) -> &mut Annotations {
+        &mut self.annotations
+    }
+
+    fn field_annotations_mut(&mut self, field: &str) -> Option<&mut Annotations> {
+        self.fields
+            .iter_mut()
+            .find(|f| f.snakecase_name == field)
+            .map(|f| &mut f.annotations)
+    }
+}
+
+fn apply_to<T: Annotated>(
+    items: &mut [T],
+    overlay: &BTreeMap<String, Annotations>,
+    kind: &str,
+) -> Result<(), String> {
+    for (key, annotations) in overlay.iter() {
+        let (name, field) = match key.split_once('.') {
+            Some((name, field)) => (name, Some(field)),
+            None => (key.as_str(), None),
+        };
+
+        let item = items
+            .iter_mut()
+            .find(|item| item.name() == name)
+            .ok_or_else(|| format!("unknown {} {:?}", kind, name))?;
+
+        let target = match field {
+            Some(field) => item
+                .field_annotations_mut(field)
+                .ok_or_else(|| format!("unknown field {:?} of {} {}", field, kind, name))?,
+            None => item.annotations_mut(),
+        };
+        target.extend(annotations.clone());
+    }
+    Ok(())
+}
+
+impl Overlay {
+    pub fn from_json(src: &str) -> Result<Self, String> {
+        serde_json::from_str(src).map_err(|e| e.to_string())
+    }
+
+    /// Loads a file, format is detected by extension (same as [`Defs::from_file`](crate::Defs::from_file)).
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, String> {
+        crate::defs::load_file(path.as_ref())
+    }
+
+    /// Merges annotations into `defs`, existing keys are overwritten.
+    pub fn apply(&self, defs: &mut crate::Defs) -> Result<(), String> {
+        apply_to(&mut defs.nodes, &self.nodes, "node")?;
+        apply_to(&mut defs.messages, &self.messages, "message")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Defs, LiquidTemplate};
+
+    const OVERLAY: &str = r#"{
+        "nodes": {
+            "Send": { "ruby_class": "Send" },
+            "Send.method_name": { "ffi_name": "method", "nullable": false }
+        },
+        "messages": { "UnterminatedHeredoc.heredoc_id": { "ffi_name": "id" } }
+    }"#;
+
+    #[test]
+    fn test_apply() {
+        let overlay = Overlay::from_json(OVERLAY).unwrap();
+        let defs = Defs::builtin().with_overlay(&overlay).unwrap();
+
+        let send = defs
+            .nodes
+            .iter()
+            .find(|n| n.camelcase_name == "Send")
+            .unwrap();
+        assert_eq!(send.annotations["ruby_class"], "Send");
+        let method_name = send
+            .fields
+            .iter()
+            .find(|f| f.snakecase_name == "method_name")
+            .unwrap();
+        assert_eq!(method_name.annotations["ffi_name"], "method");
+        assert_eq!(method_name.annotations["nullable"], false);
+    }
+
+    #[test]
+    fn test_unknown_names() {
+        let overlay = Overlay::from_json(r#"{ "nodes": { "Unknown": {} } }"#).unwrap();
+        assert_eq!(
+            Defs::builtin().with_overlay(&overlay).unwrap_err(),
+            "unknown node \"Unknown\""
+        );
+
+        let overlay =
+            Overlay::from_json(r#"{ "messages": { "UnterminatedHeredoc.x": {} } }"#).unwrap();
+        assert_eq!(
+            Defs::builtin().with_overlay(&overlay).unwrap_err(),
+            "unknown field \"x\" of message UnterminatedHeredoc"
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let overlay = Overlay::from_json(OVERLAY).unwrap();
+        let defs = Defs::builtin().with_overlay(&overlay).unwrap();
+        let output = LiquidTemplate::new_eval(
+            "{% for node in nodes %}{% for field in node.fields %}{% if field.annotations.ffi_name %}{{ field.node.annotations.ruby_class }}#{{ field.annotations.ffi_name }}{% endif %}{% endfor %}{% endfor %}",
+        )
+        .with_defs(&defs)
+        .render();
+        assert_eq!(output, "Send#method");
+    }
+
+    #[test]
+    fn test_render_strict() {
+        let overlay = Overlay::from_json(OVERLAY).unwrap();
+        let defs = Defs::builtin().with_overlay(&overlay).unwrap();
+        let output = LiquidTemplate::new_eval(
+            "{% for node in nodes %}{% for field in node.fields %}{% if field.annotations contains 'ffi_name' %}{{ field.snakecase_name }}{% endif %}{% endfor %}{% endfor %}",
+        )
+        .with_defs(&defs)
+        .strict()
+        .render();
+        assert_eq!(output, "method_name");
+    }
+}
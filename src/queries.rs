@@ -0,0 +1,130 @@
+// Query filters (`with_field_type`, `node_fields`, ...) shared by all template engines.
+//
+// Items are either nodes/messages (objects with `fields`) or fields (objects with `field_type`),
+// each engine provides access to its own value type via `Item`.
+
+use crate::NodeFieldType;
+
+pub(crate) trait Item: Sized {
+    fn str_attr(&self, name: &str) -> Option<String>;
+    fn list_attr(&self, name: &str) -> Option<Vec<Self>>;
+}
+
+// Field types of an item: all fields for a node/message, own type for a field
+fn field_types<V: Item>(item: &V) -> Vec<String> {
+    match item.list_attr("fields") {
+        Some(fields) => fields
+            .iter()
+            .filter_map(|field| field.str_attr("field_type"))
+            .collect(),
+        None => item.str_attr("field_type").into_iter().collect(),
+    }
+}
+
+/// Nodes/messages that have a field of a given type, or fields of a given type.
+pub(crate) fn with_field_type<V: Item>(items: Vec<V>, field_type: &str) -> Vec<V> {
+    items
+        .into_iter()
+        .filter(|item| field_types(item).iter().any(|t| t == field_type))
+        .collect()
+}
+
+/// Nodes/messages that have a field with a given name.
+pub(crate) fn having_field<V: Item>(items: Vec<V>, name: &str) -> Vec<V> {
+    items
+        .into_iter()
+        .filter(|item| {
+            item.list_attr("fields")
+                .unwrap_or_default()
+                .iter()
+                .any(|field| field.str_attr("snakecase_name").as_deref() == Some(name))
+        })
+        .collect()
+}
+
+// Node fields whose type matches a predicate, message fields never match
+fn fields_of_types<V: Item>(fields: Vec<V>, predicate: fn(&NodeFieldType) -> bool) -> Vec<V> {
+    fields
+        .into_iter()
+        .filter(|field| {
+            field
+                .str_attr("field_type")
+                .and_then(|t| serde_json::from_value::<NodeFieldType>(t.into()).ok())
+                .map(|t| predicate(&t))
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+/// Fields that hold child nodes (`Node`, `Nodes`, `MaybeNode`, `RegexpOptions`).
+pub(crate) fn node_fields<V: Item>(fields: Vec<V>) -> Vec<V> {
+    fields_of_types(fields, NodeFieldType::is_node)
+}
+
+/// Fields that hold locations (`Loc`, `MaybeLoc`).
+pub(crate) fn loc_fields<V: Item>(fields: Vec<V>) -> Vec<V> {
+    fields_of_types(fields, NodeFieldType::is_loc)
+}
+
+/// Unique field types in order of first occurrence.
+pub(crate) fn distinct_types<V: Item>(items: Vec<V>) -> Vec<String> {
+    let mut types: Vec<String> = vec![];
+    for item in items.iter() {
+        for field_type in field_types(item) {
+            if !types.contains(&field_type) {
+                types.push(field_type);
+            }
+        }
+    }
+    types
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::LiquidTemplate;
+
+    fn render(src: &str) -> String {
+        LiquidTemplate::new_eval(src).render()
+    }
+
+    #[test]
+    fn test_with_field_type() {
+        assert_eq!(
+            render("{{ nodes | with_field_type: 'RegexpOptions' | map: 'camelcase_name' | join: ',' }}"),
+            "Regexp"
+        );
+        assert_eq!(
+            render("{{ nodes[0].fields | with_field_type: 'Loc' | map: 'snakecase_name' | join: ',' }}"),
+            "keyword_l,expression_l"
+        );
+    }
+
+    #[test]
+    fn test_having_field() {
+        assert_eq!(
+            render("{{ nodes | having_field: 'keyword_l' | size }} {{ nodes | having_field: 'unknown' | size }}"),
+            "30 0"
+        );
+    }
+
+    #[test]
+    fn test_node_and_loc_fields() {
+        assert_eq!(
+            render("{{ nodes[0].fields | node_fields | map: 'snakecase_name' | join: ',' }}"),
+            "to,from"
+        );
+        assert_eq!(render("{{ nodes[0].fields | loc_fields | size }}"), "2");
+    }
+
+    #[test]
+    fn test_distinct_types() {
+        assert_eq!(
+            render("{{ nodes[0].fields | distinct_types | join: ',' }}"),
+            "Node,Loc"
+        );
+        assert_eq!(
+            render("{{ messages | distinct_types | join: ',' }}"),
+            "Str,Byte"
+        );
+    }
+}
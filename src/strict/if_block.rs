@@ -0,0 +1,196 @@
+// Strict `if`/`unless` blocks: every variable used in a condition must be defined.
+//
+// Conditions are evaluated by liquid-lib's own `if` block, this wrapper only
+// splits `elsif`/`else` branches and checks variables before evaluation.
+
+use liquid_core::error::ResultLiquidExt;
+use liquid_core::parser::BlockElement;
+use liquid_core::Result;
+use liquid_core::{BlockReflection, ParseBlock, TagBlock, TagTokenIter};
+use liquid_core::{Expression, Language, Renderable, Runtime, Template};
+use std::io::Write;
+
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct IfBlock;
+
+impl BlockReflection for IfBlock {
+    fn start_tag(&self) -> &str {
+        "if"
+    }
+
+    fn end_tag(&self) -> &str {
+        "endif"
+    }
+
+    fn description(&self) -> &str {
+        "Strict version of `if` block."
+    }
+}
+
+impl ParseBlock for IfBlock {
+    fn parse(
+        &self,
+        arguments: TagTokenIter<'_>,
+        mut tokens: TagBlock<'_, '_>,
+        options: &Language,
+    ) -> Result<Box<dyn Renderable>> {
+        let conditional = parse_if(arguments, &mut tokens, options, true)?;
+
+        tokens.assert_empty();
+        Ok(conditional)
+    }
+
+    fn reflection(&self) -> &dyn BlockReflection {
+        self
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct UnlessBlock;
+
+impl BlockReflection for UnlessBlock {
+    fn start_tag(&self) -> &str {
+        "unless"
+    }
+
+    fn end_tag(&self) -> &str {
+        "endunless"
+    }
+
+    fn description(&self) -> &str {
+        "Strict version of `unless` block."
+    }
+}
+
+impl ParseBlock for UnlessBlock {
+    fn parse(
+        &self,
+        arguments: TagTokenIter<'_>,
+        mut tokens: TagBlock<'_, '_>,
+        options: &Language,
+    ) -> Result<Box<dyn Renderable>> {
+        let conditional = parse_if(arguments, &mut tokens, options, false)?;
+
+        tokens.assert_empty();
+        Ok(conditional)
+    }
+
+    fn reflection(&self) -> &dyn BlockReflection {
+        self
+    }
+}
+
+fn parse_if(
+    arguments: TagTokenIter<'_>,
+    tokens: &mut TagBlock<'_, '_>,
+    options: &Language,
+    mode: bool,
+) -> Result<Box<dyn Renderable>> {
+    let condition = Condition::parse(arguments)?;
+
+    let mut if_true = Vec::new();
+    let mut if_false = None;
+
+    while let Some(element) = tokens.next()? {
+        match element {
+            BlockElement::Tag(tag) => match tag.name() {
+                "else" => {
+                    if_false = Some(tokens.parse_all(options)?);
+                    break;
+                }
+                "elsif" if mode => {
+                    if_false = Some(vec![parse_if(tag.into_tokens(), tokens, options, true)?]);
+                    break;
+                }
+                _ => if_true.push(tag.parse(tokens, options)?),
+            },
+            element => if_true.push(element.parse(tokens, options)?),
+        }
+    }
+
+    Ok(Box::new(Conditional {
+        condition,
+        mode,
+        if_true: Template::new(if_true),
+        if_false: if_false.map(Template::new),
+    }))
+}
+
+#[derive(Debug)]
+struct Conditional {
+    condition: Condition,
+    mode: bool,
+    if_true: Template,
+    if_false: Option<Template>,
+}
+
+impl Renderable for Conditional {
+    fn render_to(&self, writer: &mut dyn Write, runtime: &dyn Runtime) -> Result<()> {
+        let trace = || format!("{{% if {} %}}", self.condition.source).into();
+        let condition = self.condition.evaluate(runtime).trace_with(trace)?;
+
+        if condition == self.mode {
+            self.if_true.render_to(writer, runtime).trace_with(trace)?;
+        } else if let Some(ref template) = self.if_false {
+            template
+                .render_to(writer, runtime)
+                .trace("{{% else %}}")
+                .trace_with(trace)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct Condition {
+    source: String,
+    variables: Vec<Expression>,
+    // liquid-lib `{% if <source> %}true{% endif %}`
+    test: Template,
+}
+
+const OPERATORS: &[&str] = &[
+    "==", "!=", "<>", "<", ">", "<=", ">=", "contains", "and", "or",
+];
+
+impl Condition {
+    fn parse(arguments: TagTokenIter<'_>) -> Result<Self> {
+        let mut source = vec![];
+        let mut variables = vec![];
+        for token in arguments {
+            source.push(token.as_str().to_string());
+            if OPERATORS.contains(&token.as_str()) {
+                continue;
+            }
+            let value = token.expect_value().into_result()?;
+            if let Expression::Variable(_) = value {
+                variables.push(value);
+            }
+        }
+        let source = source.join(" ");
+
+        let mut language = Language::empty();
+        language
+            .blocks
+            .register(String::from("if"), liquid_lib::stdlib::IfBlock.into());
+        let test = liquid_core::parser::parse(
+            &format!("{{% if {} %}}true{{% endif %}}", source),
+            &language,
+        )?;
+
+        Ok(Self {
+            source,
+            variables,
+            test: Template::new(test),
+        })
+    }
+
+    fn evaluate(&self, runtime: &dyn Runtime) -> Result<bool> {
+        // `evaluate` (unlike `try_evaluate` used by liquid-lib) fails on unknown variables
+        for variable in self.variables.iter() {
+            variable.evaluate(runtime)?;
+        }
+        Ok(self.test.render(runtime)? == "true")
+    }
+}
@@ -0,0 +1,54 @@
+use crate::filters::invalid_input;
+use liquid_core::Expression;
+use liquid_core::Result;
+use liquid_core::Runtime;
+use liquid_core::{
+    Display_filter, Filter, FilterParameters, FilterReflection, FromFilterParameters, ParseFilter,
+};
+use liquid_core::{Value, ValueView};
+
+#[derive(Debug, FilterParameters)]
+struct MapArgs {
+    #[parameter(
+        description = "The property to be extracted from the values in the input.",
+        arg_type = "str"
+    )]
+    property: Expression,
+}
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "map",
+    description = "Extract `property` from the objects of an array, fails on unknown property.",
+    parameters(MapArgs),
+    parsed(MapFilter)
+)]
+pub(crate) struct Map;
+
+#[derive(Debug, FromFilterParameters, Display_filter)]
+#[name = "map"]
+struct MapFilter {
+    #[parameters]
+    args: MapArgs,
+}
+
+impl Filter for MapFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> Result<Value> {
+        let args = self.args.evaluate(runtime)?;
+
+        let array = input
+            .as_array()
+            .ok_or_else(|| invalid_input("Array expected"))?;
+
+        let result = array
+            .values()
+            .map(|v| {
+                let object = v
+                    .as_object()
+                    .ok_or_else(|| invalid_input("Array of objects expected"))?;
+                super::get_property(object, &args.property).map(|v| v.to_value())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Value::array(result))
+    }
+}
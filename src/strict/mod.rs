@@ -0,0 +1,99 @@
+// Replacements for Liquid stdlib blocks and filters that silently treat
+// unknown variables/properties as `nil`. Registered only in strict mode.
+
+mod if_block;
+mod map;
+mod where_filter;
+
+use liquid_core::model::{ObjectView, ValueView};
+
+fn unknown_index(object: &dyn ObjectView, property: &str) -> liquid_core::Error {
+    let available = object
+        .keys()
+        .map(|key| key.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    liquid_core::Error::with_msg("Unknown index")
+        .context("requested index", property.to_string())
+        .context("available indexes", available)
+}
+
+fn get_property<'o>(
+    object: &'o dyn ObjectView,
+    property: &str,
+) -> liquid_core::Result<&'o dyn ValueView> {
+    object
+        .get(property)
+        .ok_or_else(|| unknown_index(object, property))
+}
+
+pub(crate) fn blocks() -> Vec<Box<dyn liquid_core::parser::ParseBlock>> {
+    vec![if_block::IfBlock.into(), if_block::UnlessBlock.into()]
+}
+
+pub(crate) fn filters() -> Vec<Box<dyn liquid_core::parser::ParseFilter>> {
+    vec![map::Map.into(), where_filter::Where.into()]
+}
+
+#[cfg(test)]
+mod tests {
+    fn render(src: &str) -> liquid_core::Result<String> {
+        let mut builder = liquid::ParserBuilder::with_stdlib();
+        for block in super::blocks() {
+            builder = builder.block(block);
+        }
+        for filter in super::filters() {
+            builder = builder.filter(filter);
+        }
+        let globals = liquid::object!({
+            "nodes": crate::nodes(),
+            "flag": false,
+        });
+        builder.build()?.parse(src)?.render(&globals)
+    }
+
+    #[test]
+    fn test_if() {
+        assert_eq!(render("{% if flag %}a{% else %}b{% endif %}").unwrap(), "b");
+        assert_eq!(
+            render("{% if nodes.first.camelcase_name == 'Alias' %}a{% endif %}").unwrap(),
+            "a"
+        );
+        assert_eq!(
+            render("{% if flag %}a{% elsif nodes.first.wqp_name %}b{% endif %}").unwrap(),
+            "b"
+        );
+        assert!(render("{% if unknown %}a{% endif %}").is_err());
+        assert!(render("{% if flag %}a{% elsif nodes.first.wqp_nme %}b{% endif %}").is_err());
+        assert!(render("{% unless nodes.first.wqp_nme %}a{% endunless %}").is_err());
+        assert_eq!(
+            render("{% unless flag %}a{% else %}b{% endunless %}").unwrap(),
+            "a"
+        );
+        assert_eq!(
+            render("{% if nodes.size > 100 and nodes.first.wqp_name contains 'ali' %}a{% endif %}")
+                .unwrap(),
+            "a"
+        );
+        // all variables of a condition are checked, even if `or` is already true
+        assert!(render("{% if true or unknown %}a{% endif %}").is_err());
+    }
+
+    #[test]
+    fn test_map() {
+        assert_eq!(
+            render("{{ nodes | map: 'camelcase_name' | first }}").unwrap(),
+            "Alias"
+        );
+        assert!(render("{{ nodes | map: 'camelcase_nme' | first }}").is_err());
+    }
+
+    #[test]
+    fn test_where() {
+        assert_eq!(
+            render("{{ nodes | where: 'camelcase_name', 'Def' | size }}").unwrap(),
+            "1"
+        );
+        assert!(render("{{ nodes | where: 'camelcase_nme', 'Def' | size }}").is_err());
+    }
+}
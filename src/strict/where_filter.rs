@@ -0,0 +1,63 @@
+use crate::filters::invalid_input;
+use liquid_core::model::ValueViewCmp;
+use liquid_core::Expression;
+use liquid_core::Result;
+use liquid_core::Runtime;
+use liquid_core::{
+    Display_filter, Filter, FilterParameters, FilterReflection, FromFilterParameters, ParseFilter,
+};
+use liquid_core::{Value, ValueView};
+
+#[derive(Debug, FilterParameters)]
+struct WhereArgs {
+    #[parameter(description = "The property being matched", arg_type = "str")]
+    property: Expression,
+    #[parameter(
+        description = "The value the property is matched with",
+        arg_type = "any"
+    )]
+    target_value: Option<Expression>,
+}
+
+#[derive(Clone, ParseFilter, FilterReflection)]
+#[filter(
+    name = "where",
+    description = "Filter the elements of an array to those with a certain property value, fails on unknown property.",
+    parameters(WhereArgs),
+    parsed(WhereFilter)
+)]
+pub(crate) struct Where;
+
+#[derive(Debug, FromFilterParameters, Display_filter)]
+#[name = "where"]
+struct WhereFilter {
+    #[parameters]
+    args: WhereArgs,
+}
+
+impl Filter for WhereFilter {
+    fn evaluate(&self, input: &dyn ValueView, runtime: &dyn Runtime) -> Result<Value> {
+        let args = self.args.evaluate(runtime)?;
+
+        let objects = match input.as_array() {
+            Some(array) => array.values().collect::<Vec<_>>(),
+            None => vec![input],
+        };
+
+        let mut result = vec![];
+        for object in objects {
+            let object = object
+                .as_object()
+                .ok_or_else(|| invalid_input("Array of objects or a single object expected"))?;
+            let value = super::get_property(object, &args.property)?;
+            let matches = match &args.target_value {
+                None => value.query_state(liquid_core::model::State::Truthy),
+                Some(target_value) => *target_value == ValueViewCmp::new(value),
+            };
+            if matches {
+                result.push(object.to_value());
+            }
+        }
+        Ok(Value::array(result))
+    }
+}
@@ -0,0 +1,243 @@
+//! Rendering of strings as string literals of target languages.
+//!
+//! Output is always ASCII-only: quotes, backslashes and control characters get
+//! their usual escapes and non-ASCII characters are written as unicode escapes
+//! (or as octal-escaped UTF-8 bytes in C/C++, where string literals are byte strings).
+
+use crate::keywords::Language;
+
+/// Renders `s` as a double-quoted string literal of a given language.
+pub fn string_literal(s: &str, language: Language) -> String {
+    let mut out = String::from("\"");
+    let chars = s.chars().collect::<Vec<_>>();
+    for (idx, c) in chars.iter().copied().enumerate() {
+        match language {
+            Language::Rust2015 | Language::Rust2018 | Language::Rust2021 => {
+                escape_rust(&mut out, c)
+            }
+            Language::C | Language::Cpp => {
+                let next = chars.get(idx + 1).copied();
+                escape_c(&mut out, c, next)
+            }
+            Language::JavaScript | Language::TypeScript => escape_js(&mut out, c),
+            Language::Java => escape_java(&mut out, c),
+            Language::Kotlin => escape_kotlin(&mut out, c),
+            Language::CSharp => escape_csharp(&mut out, c),
+            Language::Python => escape_python(&mut out, c),
+            Language::Go => escape_go(&mut out, c),
+            Language::Swift => escape_swift(&mut out, c),
+            Language::Ruby => escape_ruby(&mut out, c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Same as [`string_literal`], but language is given by name (like `"cpp"`), used by template filters.
+pub fn string_literal_by_name(s: &str, language: &str) -> Result<String, String> {
+    let language =
+        Language::from_name(language).ok_or_else(|| format!("unknown language {}", language))?;
+    Ok(string_literal(s, language))
+}
+
+fn is_printable_ascii(c: char) -> bool {
+    (' '..='~').contains(&c)
+}
+
+// \uXXXX, non-BMP characters are written as surrogate pairs
+fn push_utf16_escapes(out: &mut String, c: char) {
+    let mut buf = [0; 2];
+    for unit in c.encode_utf16(&mut buf) {
+        out.push_str(&format!("\\u{:04x}", unit));
+    }
+}
+
+fn escape_rust(out: &mut String, c: char) {
+    match c {
+        '"' => out.push_str("\\\""),
+        '\\' => out.push_str("\\\\"),
+        '\n' => out.push_str("\\n"),
+        '\r' => out.push_str("\\r"),
+        '\t' => out.push_str("\\t"),
+        '\0' => out.push_str("\\0"),
+        c if is_printable_ascii(c) => out.push(c),
+        c => out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+    }
+}
+
+fn escape_c(out: &mut String, c: char, next: Option<char>) {
+    match c {
+        '"' => out.push_str("\\\""),
+        '\\' => out.push_str("\\\\"),
+        '\n' => out.push_str("\\n"),
+        '\r' => out.push_str("\\r"),
+        '\t' => out.push_str("\\t"),
+        '\x07' => out.push_str("\\a"),
+        '\x08' => out.push_str("\\b"),
+        '\x0b' => out.push_str("\\v"),
+        '\x0c' => out.push_str("\\f"),
+        // "??" can start a trigraph
+        '?' if next == Some('?') => out.push_str("?\\"),
+        c if is_printable_ascii(c) => out.push(c),
+        c => {
+            // octal escapes take at most 3 digits, unlike greedy \x
+            let mut buf = [0; 4];
+            for byte in c.encode_utf8(&mut buf).bytes() {
+                out.push_str(&format!("\\{:03o}", byte));
+            }
+        }
+    }
+}
+
+fn escape_js(out: &mut String, c: char) {
+    match c {
+        '"' => out.push_str("\\\""),
+        '\\' => out.push_str("\\\\"),
+        '\n' => out.push_str("\\n"),
+        '\r' => out.push_str("\\r"),
+        '\t' => out.push_str("\\t"),
+        c if is_printable_ascii(c) => out.push(c),
+        c => push_utf16_escapes(out, c),
+    }
+}
+
+fn escape_java(out: &mut String, c: char) {
+    match c {
+        '"' => out.push_str("\\\""),
+        '\\' => out.push_str("\\\\"),
+        '\n' => out.push_str("\\n"),
+        '\r' => out.push_str("\\r"),
+        '\t' => out.push_str("\\t"),
+        '\x08' => out.push_str("\\b"),
+        '\x0c' => out.push_str("\\f"),
+        c if is_printable_ascii(c) => out.push(c),
+        // \u escapes are translated before lexing, so \u000a would terminate the literal
+        c if (c as u32) < 0x80 => out.push_str(&format!("\\{:03o}", c as u32)),
+        c => push_utf16_escapes(out, c),
+    }
+}
+
+fn escape_kotlin(out: &mut String, c: char) {
+    match c {
+        '$' => out.push_str("\\$"),
+        '"' => out.push_str("\\\""),
+        '\\' => out.push_str("\\\\"),
+        '\n' => out.push_str("\\n"),
+        '\r' => out.push_str("\\r"),
+        '\t' => out.push_str("\\t"),
+        c if is_printable_ascii(c) => out.push(c),
+        c => push_utf16_escapes(out, c),
+    }
+}
+
+fn escape_csharp(out: &mut String, c: char) {
+    match c {
+        '"' => out.push_str("\\\""),
+        '\\' => out.push_str("\\\\"),
+        '\n' => out.push_str("\\n"),
+        '\r' => out.push_str("\\r"),
+        '\t' => out.push_str("\\t"),
+        c if is_printable_ascii(c) => out.push(c),
+        // \x is variable-length in C#, \u always takes 4 digits
+        c => push_utf16_escapes(out, c),
+    }
+}
+
+fn escape_python(out: &mut String, c: char) {
+    match c {
+        '"' => out.push_str("\\\""),
+        '\\' => out.push_str("\\\\"),
+        '\n' => out.push_str("\\n"),
+        '\r' => out.push_str("\\r"),
+        '\t' => out.push_str("\\t"),
+        c if is_printable_ascii(c) => out.push(c),
+        c if (c as u32) < 0x100 => out.push_str(&format!("\\x{:02x}", c as u32)),
+        c if (c as u32) < 0x10000 => out.push_str(&format!("\\u{:04x}", c as u32)),
+        c => out.push_str(&format!("\\U{:08x}", c as u32)),
+    }
+}
+
+fn escape_go(out: &mut String, c: char) {
+    match c {
+        '"' => out.push_str("\\\""),
+        '\\' => out.push_str("\\\\"),
+        '\n' => out.push_str("\\n"),
+        '\r' => out.push_str("\\r"),
+        '\t' => out.push_str("\\t"),
+        c if is_printable_ascii(c) => out.push(c),
+        c if (c as u32) < 0x10000 => out.push_str(&format!("\\u{:04x}", c as u32)),
+        c => out.push_str(&format!("\\U{:08x}", c as u32)),
+    }
+}
+
+fn escape_swift(out: &mut String, c: char) {
+    match c {
+        '"' => out.push_str("\\\""),
+        '\\' => out.push_str("\\\\"),
+        '\n' => out.push_str("\\n"),
+        '\r' => out.push_str("\\r"),
+        '\t' => out.push_str("\\t"),
+        '\0' => out.push_str("\\0"),
+        c if is_printable_ascii(c) => out.push(c),
+        c => out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+    }
+}
+
+fn escape_ruby(out: &mut String, c: char) {
+    match c {
+        '"' => out.push_str("\\\""),
+        '\\' => out.push_str("\\\\"),
+        // "#{", "#@" and "#$" start interpolation
+        '#' => out.push_str("\\#"),
+        '\n' => out.push_str("\\n"),
+        '\r' => out.push_str("\\r"),
+        '\t' => out.push_str("\\t"),
+        '\x1b' => out.push_str("\\e"),
+        c if is_printable_ascii(c) => out.push(c),
+        c => out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "Sym(\"foo\") \\u{1} #{x}\n\té\u{1}??=😀";
+
+    fn literal(language: &str) -> String {
+        string_literal_by_name(INPUT, language).unwrap()
+    }
+
+    #[test]
+    fn test_string_literal() {
+        assert_eq!(
+            literal("rust"),
+            r#""Sym(\"foo\") \\u{1} #{x}\n\t\u{e9}\u{1}??=\u{1f600}""#
+        );
+        assert_eq!(
+            literal("c"),
+            r#""Sym(\"foo\") \\u{1} #{x}\n\t\303\251\001?\?=\360\237\230\200""#
+        );
+        assert_eq!(
+            literal("js"),
+            r#""Sym(\"foo\") \\u{1} #{x}\n\t\u00e9\u0001??=\ud83d\ude00""#
+        );
+        assert_eq!(
+            literal("java"),
+            r#""Sym(\"foo\") \\u{1} #{x}\n\t\u00e9\001??=\ud83d\ude00""#
+        );
+        assert_eq!(
+            literal("python"),
+            r#""Sym(\"foo\") \\u{1} #{x}\n\t\xe9\x01??=\U0001f600""#
+        );
+        assert_eq!(
+            literal("ruby"),
+            r#""Sym(\"foo\") \\u{1} \#{x}\n\t\u{e9}\u{1}??=\u{1f600}""#
+        );
+    }
+
+    #[test]
+    fn test_unknown_language() {
+        assert!(string_literal_by_name("foo", "cobol").is_err());
+    }
+}
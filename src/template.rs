@@ -0,0 +1,69 @@
+/// Common interface of all supported template engines.
+///
+/// [`LiquidTemplate`](crate::LiquidTemplate) is always available,
+/// `MiniJinjaTemplate` and `TeraTemplate` are enabled by `minijinja` and `tera` cargo features.
+///
+/// Engines implement [`render_in_memory`](Self::render_in_memory) and report their files,
+/// `render`/`dependencies`/`check` are shared.
+///
+/// Strict mode (errors on undefined variables) is engine-specific: `LiquidTemplate::strict`
+/// and `MiniJinjaTemplate::strict`. `TeraTemplate` has no strict mode, Tera already fails
+/// on undefined variables in `{{ output }}` but treats them as falsy in `{% if %}`.
+pub trait Template: Sized {
+    /// Renders the template without any side effects.
+    fn render_in_memory(self) -> String;
+
+    /// Path of the template file, `None` for templates created with `new_eval`.
+    fn source_path(&self) -> Option<&str>;
+
+    /// Paths of all registered partials.
+    fn partial_paths(&self) -> Vec<&str>;
+
+    /// Renders the template, in a build script also emits `cargo:rerun-if-changed`
+    /// for all [`dependencies`](Self::dependencies).
+    fn render(self) -> String {
+        if crate::build_support::is_build_script() {
+            for path in self.dependencies() {
+                crate::build_support::rerun_if_changed(path);
+            }
+        }
+        self.render_in_memory()
+    }
+
+    /// Files that affect the output: the template itself (if it was read from disk) and all partials.
+    fn dependencies(&self) -> Vec<&str> {
+        let mut deps: Vec<&str> = self.source_path().into_iter().collect();
+        deps.extend(self.partial_paths());
+        deps
+    }
+
+    /// Renders the template in memory and compares it with the file at `path`.
+    ///
+    /// Returns `true` if the file is up to date. Otherwise prints a unified diff
+    /// between the file on disk and the rendered output to stderr and returns `false`.
+    fn check<P: AsRef<str>>(self, path: P) -> bool {
+        let rendered = self.render_in_memory();
+        crate::build_support::check_file(path.as_ref(), &rendered)
+    }
+}
+
+pub(crate) struct Partial {
+    pub(crate) name: String,
+    pub(crate) path: String,
+    pub(crate) src: String,
+}
+
+impl Partial {
+    pub(crate) fn read<N: AsRef<str>, P: AsRef<str>>(name: N, path: P) -> Self {
+        let path = path.as_ref().to_string();
+        let src = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+            eprintln!("Failed to read partial {}:\n{}", path, e);
+            std::process::exit(1);
+        });
+        Self {
+            name: name.as_ref().to_string(),
+            path,
+            src,
+        }
+    }
+}
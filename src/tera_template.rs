@@ -0,0 +1,429 @@
+use crate::defs::Defs;
+use crate::helpers;
+use crate::naming::{Acronyms, Case};
+use crate::template::Partial;
+use crate::type_maps::TypeMap;
+use std::collections::{BTreeMap, HashMap};
+use tera::{Context, Tera, Value};
+
+pub struct TeraTemplate {
+    path: String,
+    src: String,
+    from_file: bool,
+    partials: Vec<Partial>,
+    acronyms: Acronyms,
+    type_maps: BTreeMap<String, TypeMap>,
+    globals: BTreeMap<String, Value>,
+}
+
+fn default_globals(template: &str) -> BTreeMap<String, Value> {
+    let defs = Defs::builtin();
+    let mut globals = BTreeMap::new();
+    globals.insert(
+        String::from("nodes"),
+        tera::to_value(crate::globals::nodes(&defs.nodes)).unwrap(),
+    );
+    globals.insert(
+        String::from("messages"),
+        tera::to_value(crate::globals::messages(&defs.messages)).unwrap(),
+    );
+    globals.insert(String::from("template"), Value::from(template));
+    globals
+}
+
+impl TeraTemplate {
+    pub fn new<P: AsRef<str>>(path: P) -> Self {
+        let path = path.as_ref().to_string();
+        let src = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+            eprintln!("Failed to read {}:\n{}", path, e);
+            std::process::exit(1);
+        });
+        Self {
+            globals: default_globals(&path),
+            path,
+            src,
+            from_file: true,
+            partials: vec![],
+            acronyms: Acronyms::default(),
+            type_maps: TypeMap::builtins(),
+        }
+    }
+
+    pub fn new_eval<S: AsRef<str>>(src: S) -> Self {
+        Self {
+            path: String::from("eval.tera"),
+            src: src.as_ref().to_string(),
+            from_file: false,
+            partials: vec![],
+            acronyms: Acronyms::default(),
+            type_maps: TypeMap::builtins(),
+            globals: default_globals("eval.tera"),
+        }
+    }
+
+    pub fn with_partial<N: AsRef<str>, P: AsRef<str>>(mut self, name: N, path: P) -> Self {
+        self.partials.push(Partial::read(name, path));
+        self
+    }
+
+    /// Registers words that case conversion filters (`to_snakecase` and others)
+    /// must keep as a single word, see [`naming::Acronyms`](crate::naming::Acronyms).
+    pub fn with_acronyms<S: AsRef<str>>(mut self, words: &[S]) -> Self {
+        for word in words {
+            self.acronyms.push(word);
+        }
+        self
+    }
+
+    /// Registers a type map for `map_type` filter, replaces a built-in one with the same name.
+    pub fn with_type_map(mut self, name: &str, type_map: TypeMap) -> Self {
+        self.type_maps.insert(name.to_owned(), type_map);
+        self
+    }
+
+    pub fn with_global(mut self, name: &str, value: Value) -> Self {
+        self.globals.insert(name.to_owned(), value);
+        self
+    }
+
+    /// Replaces `nodes` and `messages` globals with a given set (by default it's [`Defs::builtin`]).
+    pub fn with_defs(self, defs: &Defs) -> Self {
+        self.with_serde_global("nodes", &crate::globals::nodes(&defs.nodes))
+            .with_serde_global("messages", &crate::globals::messages(&defs.messages))
+    }
+
+    /// Same as [`with_global`](Self::with_global), but takes any `Serialize` value.
+    pub fn with_serde_global<T: serde::Serialize>(self, name: &str, value: &T) -> Self {
+        let value = tera::to_value(value).unwrap_or_else(|e| {
+            eprintln!("Failed to convert global {} to Tera value:\n{}", name, e);
+            std::process::exit(1);
+        });
+        self.with_global(name, value)
+    }
+
+    /// See [`Template::render`](crate::Template::render).
+    pub fn render(self) -> String {
+        crate::Template::render(self)
+    }
+
+    /// See [`Template::dependencies`](crate::Template::dependencies).
+    pub fn dependencies(&self) -> Vec<&str> {
+        crate::Template::dependencies(self)
+    }
+
+    /// See [`Template::check`](crate::Template::check).
+    pub fn check<P: AsRef<str>>(self, path: P) -> bool {
+        crate::Template::check(self, path)
+    }
+}
+
+impl crate::Template for TeraTemplate {
+    fn source_path(&self) -> Option<&str> {
+        if self.from_file {
+            Some(&self.path)
+        } else {
+            None
+        }
+    }
+
+    fn partial_paths(&self) -> Vec<&str> {
+        self.partials.iter().map(|p| p.path.as_str()).collect()
+    }
+
+    fn render_in_memory(self) -> String {
+        let Self {
+            path,
+            src,
+            partials,
+            acronyms,
+            type_maps,
+            globals,
+            ..
+        } = self;
+
+        let mut tera = Tera::default();
+        tera.autoescape_on(vec![]);
+        register_filters(&mut tera, &acronyms, type_maps);
+
+        let mut templates = vec![(path.as_str(), src.as_str())];
+        for partial in partials.iter() {
+            templates.push((partial.name.as_str(), partial.src.as_str()));
+        }
+        tera.add_raw_templates(templates).unwrap_or_else(|e| {
+            eprintln!("Tera template error ({}):\n{}", path, format_error(&e));
+            std::process::exit(1);
+        });
+
+        let mut context = Context::new();
+        for (name, value) in globals {
+            context.insert(name, &value);
+        }
+
+        tera.render(&path, &context).unwrap_or_else(|e| {
+            eprintln!("Failed to render {} template:\n{}", path, format_error(&e));
+            std::process::exit(1);
+        })
+    }
+}
+
+// tera::Error only displays the outermost error, the actual cause is in the chain
+fn format_error(e: &tera::Error) -> String {
+    let mut message = e.to_string();
+    let mut source = std::error::Error::source(e);
+    while let Some(cause) = source {
+        message.push_str(&format!("\n{}", cause));
+        source = cause.source();
+    }
+    message
+}
+
+fn string_filter(f: fn(&str) -> String) -> impl tera::Filter {
+    move |value: &Value, _args: &HashMap<String, Value>| {
+        let s = tera::try_get_value!("string_filter", "value", String, value);
+        Ok(Value::from(f(&s)))
+    }
+}
+
+fn render_comment(value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let lines = tera::try_get_value!("render_comment", "value", Vec<String>, value);
+    let prefix = match args.get("prefix") {
+        Some(prefix) => tera::try_get_value!("render_comment", "prefix", String, prefix),
+        None => return Err(tera::Error::msg("prefix argument is required")),
+    };
+    let offset = match args.get("offset") {
+        Some(offset) => tera::try_get_value!("render_comment", "offset", usize, offset),
+        None => return Err(tera::Error::msg("offset argument is required")),
+    };
+    Ok(Value::from(helpers::render_comment(
+        &lines, &prefix, offset,
+    )))
+}
+
+fn render_doc_comment(value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let lines = tera::try_get_value!("render_doc_comment", "value", Vec<String>, value);
+    let style = match args.get("style") {
+        Some(style) => tera::try_get_value!("render_doc_comment", "style", String, style),
+        None => return Err(tera::Error::msg("style argument is required")),
+    };
+    let offset = match args.get("offset") {
+        Some(offset) => tera::try_get_value!("render_doc_comment", "offset", usize, offset),
+        None => 0,
+    };
+    crate::doc_comments::render_doc_comment_by_name(&lines, &style, offset)
+        .map(Value::from)
+        .map_err(tera::Error::msg)
+}
+
+fn string_literal(value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let s = tera::try_get_value!("string_literal", "value", String, value);
+    let language = match args.get("language") {
+        Some(language) => tera::try_get_value!("string_literal", "language", String, language),
+        None => return Err(tera::Error::msg("language argument is required")),
+    };
+    crate::string_literals::string_literal_by_name(&s, &language)
+        .map(Value::from)
+        .map_err(tera::Error::msg)
+}
+
+fn escape_keyword(value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let s = tera::try_get_value!("escape_keyword", "value", String, value);
+    let language = match args.get("language") {
+        Some(language) => tera::try_get_value!("escape_keyword", "language", String, language),
+        None => return Err(tera::Error::msg("language argument is required")),
+    };
+    let style = match args.get("style") {
+        Some(style) => Some(tera::try_get_value!(
+            "escape_keyword",
+            "style",
+            String,
+            style
+        )),
+        None => None,
+    };
+    crate::keywords::escape_keyword_by_name(&s, &language, style.as_deref())
+        .map(Value::from)
+        .map_err(tera::Error::msg)
+}
+
+impl crate::queries::Item for Value {
+    fn str_attr(&self, name: &str) -> Option<String> {
+        self.get(name)?.as_str().map(String::from)
+    }
+
+    fn list_attr(&self, name: &str) -> Option<Vec<Self>> {
+        self.get(name)?.as_array().cloned()
+    }
+}
+
+fn query_filter(
+    name: &'static str,
+    f: fn(Vec<Value>, &str) -> Vec<Value>,
+    arg: &'static str,
+) -> impl tera::Filter {
+    move |value: &Value, args: &HashMap<String, Value>| {
+        let items = tera::try_get_value!(name, "value", Vec<Value>, value);
+        let arg = match args.get(arg) {
+            Some(value) => tera::try_get_value!(name, arg, String, value),
+            None => return Err(tera::Error::msg(format!("{} argument is required", arg))),
+        };
+        Ok(Value::from(f(items, &arg)))
+    }
+}
+
+fn fields_filter(name: &'static str, f: fn(Vec<Value>) -> Vec<Value>) -> impl tera::Filter {
+    move |value: &Value, _args: &HashMap<String, Value>| {
+        let fields = tera::try_get_value!(name, "value", Vec<Value>, value);
+        Ok(Value::from(f(fields)))
+    }
+}
+
+fn distinct_types(value: &Value, _args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let items = tera::try_get_value!("distinct_types", "value", Vec<Value>, value);
+    Ok(Value::from(crate::queries::distinct_types(items)))
+}
+
+fn register_filters(tera: &mut Tera, acronyms: &Acronyms, type_maps: BTreeMap<String, TypeMap>) {
+    tera.register_filter(
+        "map_type",
+        move |value: &Value, args: &HashMap<String, Value>| {
+            let field_type = tera::try_get_value!("map_type", "value", String, value);
+            let name = match args.get("type_map") {
+                Some(name) => tera::try_get_value!("map_type", "type_map", String, name),
+                None => return Err(tera::Error::msg("type_map argument is required")),
+            };
+            let type_map = type_maps
+                .get(&name)
+                .ok_or_else(|| tera::Error::msg(format!("unknown type map {}", name)))?;
+            type_map
+                .get(&field_type)
+                .map(Value::from)
+                .ok_or_else(|| tera::Error::msg(format!("type map {} has no {}", name, field_type)))
+        },
+    );
+    for case in Case::all() {
+        let acronyms = acronyms.clone();
+        tera.register_filter(
+            case.filter_name(),
+            move |value: &Value, _args: &HashMap<String, Value>| {
+                let s = tera::try_get_value!(case.filter_name(), "value", String, value);
+                Ok(Value::from(crate::naming::convert(&s, case, &acronyms)))
+            },
+        );
+    }
+    tera.register_filter(
+        "camelcase_to_snakecase",
+        string_filter(helpers::camelcase_to_snakecase),
+    );
+    tera.register_filter(
+        "snakecase_to_camelcase",
+        string_filter(helpers::snakecase_to_camelcase),
+    );
+    tera.register_filter(
+        "escape_rust_keyword",
+        string_filter(helpers::escape_rust_keyword),
+    );
+    tera.register_filter("escape_c_keyword", string_filter(helpers::escape_c_keyword));
+    tera.register_filter(
+        "escape_cpp_keyword",
+        string_filter(helpers::escape_cpp_keyword),
+    );
+    tera.register_filter(
+        "escape_js_keyword",
+        string_filter(helpers::escape_js_keyword),
+    );
+    tera.register_filter("escape_keyword", escape_keyword);
+    tera.register_filter("string_literal", string_literal);
+    tera.register_filter("render_comment", render_comment);
+    tera.register_filter(
+        "with_field_type",
+        query_filter(
+            "with_field_type",
+            crate::queries::with_field_type,
+            "field_type",
+        ),
+    );
+    tera.register_filter(
+        "having_field",
+        query_filter("having_field", crate::queries::having_field, "name"),
+    );
+    tera.register_filter(
+        "node_fields",
+        fields_filter("node_fields", crate::queries::node_fields),
+    );
+    tera.register_filter(
+        "loc_fields",
+        fields_filter("loc_fields", crate::queries::loc_fields),
+    );
+    tera.register_filter("distinct_types", distinct_types);
+    tera.register_filter("render_doc_comment", render_doc_comment);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TeraTemplate;
+
+    #[test]
+    fn test_render() {
+        let output = TeraTemplate::new_eval(
+            "{{ nodes | length }} {{ messages | length }} {{ 'FooBar' | camelcase_to_snakecase }}",
+        )
+        .render();
+        assert_eq!(output, "124 90 foo_bar");
+    }
+
+    #[test]
+    fn test_escape_keyword() {
+        let output =
+            TeraTemplate::new_eval("{{ 'type' | escape_keyword(language='rust', style='raw') }}")
+                .render();
+        assert_eq!(output, "r#type");
+    }
+
+    #[test]
+    fn test_map_type() {
+        let output = TeraTemplate::new_eval("{{ 'MaybeLoc' | map_type(type_map='cpp') }}").render();
+        assert_eq!(output, "std::optional<Loc>");
+    }
+
+    #[test]
+    fn test_render_doc_comment() {
+        let output = TeraTemplate::new_eval(
+            "{% for node in nodes %}{% if node.camelcase_name == 'Alias' %}{{ node.comment | render_doc_comment(style='yard', offset=2) }}{% endif %}{% endfor %}",
+        )
+        .render();
+        assert_eq!(output, "# Represents <tt>alias to from</tt> statement.");
+    }
+
+    #[test]
+    fn test_string_literal() {
+        let output =
+            TeraTemplate::new_eval("{{ 'a#{b}' | string_literal(language='ruby') }}").render();
+        assert_eq!(output, r#""a\#{b}""#);
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let output = TeraTemplate::new_eval(
+            "{{ nodes | having_field(name='keyword_l') | length }} {{ nodes.0.fields | loc_fields | length }} {{ nodes.0.fields | distinct_types | join(sep=',') }}",
+        )
+        .render();
+        assert_eq!(output, "30 2 Node,Loc");
+    }
+
+    #[test]
+    fn test_serde_global() {
+        let output = TeraTemplate::new_eval("{{ prefixes | join(sep=',') }}")
+            .with_serde_global("prefixes", &["a", "b"])
+            .render();
+        assert_eq!(output, "a,b");
+    }
+
+    #[test]
+    fn test_render_comment() {
+        let output = TeraTemplate::new_eval(
+            "{% for node in nodes %}{% if node.camelcase_name == 'Alias' %}{{ node.comment | render_comment(prefix='///', offset=0) }}{% endif %}{% endfor %}",
+        )
+        .render();
+        assert_eq!(output, "/// Represents `alias to from` statement.");
+    }
+}
@@ -0,0 +1,273 @@
+//! Mapping of `NodeFieldType`/`MessageFieldType` to types of target languages.
+
+use std::collections::BTreeMap;
+
+/// Mapping from field type name (like `MaybeNode` or `Byte`) to a type in target language.
+///
+/// Variant names of `NodeFieldType` and `MessageFieldType` share one namespace,
+/// `Str` is used by both.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeMap {
+    types: BTreeMap<String, String>,
+}
+
+impl TypeMap {
+    pub fn new<K: AsRef<str>, V: AsRef<str>>(entries: &[(K, V)]) -> Self {
+        let types = entries
+            .iter()
+            .map(|(k, v)| (k.as_ref().to_string(), v.as_ref().to_string()))
+            .collect();
+        Self { types }
+    }
+
+    /// Returns one of the built-in maps: `rust`, `c`, `cpp`, `typescript` (also `ts`),
+    /// `protobuf`, `flatbuffers`, `rbs`, `rbi` or `python`.
+    pub fn builtin(name: &str) -> Option<Self> {
+        let entries = match name {
+            "rust" => RUST,
+            "c" => C,
+            "cpp" => CPP,
+            "typescript" | "ts" => TYPESCRIPT,
+            "protobuf" => PROTOBUF,
+            "flatbuffers" => FLATBUFFERS,
+            "rbs" => RBS,
+            "rbi" => RBI,
+            "python" => PYTHON,
+            _ => return None,
+        };
+        Some(Self::new(entries))
+    }
+
+    pub fn builtins() -> BTreeMap<String, TypeMap> {
+        [
+            "rust",
+            "c",
+            "cpp",
+            "typescript",
+            "ts",
+            "protobuf",
+            "flatbuffers",
+            "rbs",
+            "rbi",
+            "python",
+        ]
+        .into_iter()
+        .map(|name| (name.to_string(), Self::builtin(name).unwrap()))
+        .collect()
+    }
+
+    /// Adds or overrides a single entry.
+    pub fn with<K: AsRef<str>, V: AsRef<str>>(mut self, field_type: K, target_type: V) -> Self {
+        self.types.insert(
+            field_type.as_ref().to_string(),
+            target_type.as_ref().to_string(),
+        );
+        self
+    }
+
+    pub fn get(&self, field_type: &str) -> Option<&str> {
+        self.types.get(field_type).map(String::as_str)
+    }
+}
+
+static RUST: &[(&str, &str)] = &[
+    ("Node", "Box<Node>"),
+    ("Nodes", "Vec<Node>"),
+    ("MaybeNode", "Option<Box<Node>>"),
+    ("RegexpOptions", "Option<Box<Node>>"),
+    ("Loc", "Loc"),
+    ("MaybeLoc", "Option<Loc>"),
+    ("Str", "String"),
+    ("RawStr", "String"),
+    ("MaybeStr", "Option<String>"),
+    ("Chars", "Option<String>"),
+    ("StringValue", "Bytes"),
+    ("U8", "u8"),
+    ("Byte", "u8"),
+];
+
+static C: &[(&str, &str)] = &[
+    ("Node", "Node*"),
+    ("Nodes", "NodeList"),
+    ("MaybeNode", "Node*"),
+    ("RegexpOptions", "Node*"),
+    ("Loc", "Loc"),
+    ("MaybeLoc", "MaybeLoc"),
+    ("Str", "String"),
+    ("RawStr", "String"),
+    ("MaybeStr", "MaybeString"),
+    ("Chars", "MaybeString"),
+    ("StringValue", "Bytes"),
+    ("U8", "uint8_t"),
+    ("Byte", "uint8_t"),
+];
+
+static CPP: &[(&str, &str)] = &[
+    ("Node", "std::unique_ptr<Node>"),
+    ("Nodes", "std::vector<Node>"),
+    ("MaybeNode", "std::unique_ptr<Node>"),
+    ("RegexpOptions", "std::unique_ptr<Node>"),
+    ("Loc", "Loc"),
+    ("MaybeLoc", "std::optional<Loc>"),
+    ("Str", "std::string"),
+    ("RawStr", "std::string"),
+    ("MaybeStr", "std::optional<std::string>"),
+    ("Chars", "std::optional<std::string>"),
+    ("StringValue", "Bytes"),
+    ("U8", "uint8_t"),
+    ("Byte", "uint8_t"),
+];
+
+// `StringValue` is a string in all generators of a serialized AST (TypeScript, Python, RBS, RBI, JSON Schema),
+// only binary formats (protobuf, FlatBuffers) keep raw bytes.
+static TYPESCRIPT: &[(&str, &str)] = &[
+    ("Node", "Node"),
+    ("Nodes", "Node[]"),
+    ("MaybeNode", "Node | null"),
+    ("RegexpOptions", "Node | null"),
+    ("Loc", "Loc"),
+    ("MaybeLoc", "Loc | null"),
+    ("Str", "string"),
+    ("RawStr", "string"),
+    ("MaybeStr", "string | null"),
+    ("Chars", "string | null"),
+    ("StringValue", "string"),
+    ("U8", "number"),
+    ("Byte", "number"),
+];
+
+static PROTOBUF: &[(&str, &str)] = &[
+    ("Node", "Node"),
+    ("Nodes", "repeated Node"),
+    ("MaybeNode", "Node"),
+    ("RegexpOptions", "Node"),
+    ("Loc", "Loc"),
+    ("MaybeLoc", "Loc"),
+    ("Str", "string"),
+    ("RawStr", "string"),
+    ("MaybeStr", "optional string"),
+    ("Chars", "optional string"),
+    ("StringValue", "bytes"),
+    ("U8", "uint32"),
+    ("Byte", "uint32"),
+];
+
+static FLATBUFFERS: &[(&str, &str)] = &[
+    ("Node", "Node"),
+    ("Nodes", "[AnyNode]"),
+    ("MaybeNode", "Node"),
+    ("RegexpOptions", "Node"),
+    ("Loc", "Loc"),
+    ("MaybeLoc", "Loc"),
+    ("Str", "string"),
+    ("RawStr", "string"),
+    ("MaybeStr", "string"),
+    ("Chars", "string"),
+    ("StringValue", "[ubyte]"),
+    ("U8", "ubyte"),
+    ("Byte", "ubyte"),
+];
+
+// Core classes are fully qualified, nodes like `Array` or `Hash` shadow them inside of `Nodes` module
+static RBS: &[(&str, &str)] = &[
+    ("Node", "Node"),
+    ("Nodes", "::Array[Node]"),
+    ("MaybeNode", "Node?"),
+    ("RegexpOptions", "Node?"),
+    ("Loc", "Loc"),
+    ("MaybeLoc", "Loc?"),
+    ("Str", "::String"),
+    ("RawStr", "::String"),
+    ("MaybeStr", "::String?"),
+    ("Chars", "::String?"),
+    ("StringValue", "::String"),
+    ("U8", "::Integer"),
+    ("Byte", "::Integer"),
+];
+
+static RBI: &[(&str, &str)] = &[
+    ("Node", "Node"),
+    ("Nodes", "T::Array[Node]"),
+    ("MaybeNode", "T.nilable(Node)"),
+    ("RegexpOptions", "T.nilable(Node)"),
+    ("Loc", "Loc"),
+    ("MaybeLoc", "T.nilable(Loc)"),
+    ("Str", "::String"),
+    ("RawStr", "::String"),
+    ("MaybeStr", "T.nilable(::String)"),
+    ("Chars", "T.nilable(::String)"),
+    ("StringValue", "::String"),
+    ("U8", "::Integer"),
+    ("Byte", "::Integer"),
+];
+
+static PYTHON: &[(&str, &str)] = &[
+    ("Node", "Node"),
+    ("Nodes", "List[Node]"),
+    ("MaybeNode", "Optional[Node]"),
+    ("RegexpOptions", "Optional[Node]"),
+    ("Loc", "Loc"),
+    ("MaybeLoc", "Optional[Loc]"),
+    ("Str", "str"),
+    ("RawStr", "str"),
+    ("MaybeStr", "Optional[str]"),
+    ("Chars", "Optional[str]"),
+    ("StringValue", "str"),
+    ("U8", "int"),
+    ("Byte", "int"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::TypeMap;
+
+    #[test]
+    fn test_builtins_are_complete() {
+        let mut field_types = vec![];
+        for node in crate::nodes() {
+            for field in node.fields {
+                field_types.push(format!("{:?}", field.field_type));
+            }
+        }
+        for message in crate::messages() {
+            for field in message.fields {
+                field_types.push(format!("{:?}", field.field_type));
+            }
+        }
+
+        for (name, type_map) in TypeMap::builtins() {
+            for field_type in field_types.iter() {
+                assert!(
+                    type_map.get(field_type).is_some(),
+                    "{} type map has no {}",
+                    name,
+                    field_type
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_string_value() {
+        for name in ["typescript", "python", "rbs", "rbi"] {
+            let string = TypeMap::builtin(name)
+                .unwrap()
+                .get("Str")
+                .unwrap()
+                .to_string();
+            assert_eq!(
+                TypeMap::builtin(name).unwrap().get("StringValue"),
+                Some(string.as_str()),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_with() {
+        let type_map = TypeMap::builtin("rust").unwrap().with("Node", "NodeRef");
+        assert_eq!(type_map.get("Node"), Some("NodeRef"));
+        assert_eq!(type_map.get("MaybeNode"), Some("Option<Box<Node>>"));
+    }
+}